@@ -0,0 +1,48 @@
+//! Compares a large map keyed by interned values under [`nohash_hasher::IntMap`], which hashes
+//! keys as their addresses, against the same map under the default hasher.
+//!
+//! Run with `cargo bench --bench nohash --features nohash-hasher`.
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use labels::intern::{Interned, Interner, NoHashInterned};
+use nohash_hasher::IntMap;
+
+const LEN: usize = 1_000_000;
+const LOOKUPS: usize = 10_000_000;
+
+/// Returns the time per operation of running `f` once per key, `total` times in all.
+fn time(keys: &[Interned<str>], total: usize, mut f: impl FnMut(Interned<str>)) -> Duration {
+    let start = Instant::now();
+    for i in 0..total {
+        f(black_box(keys[i % keys.len()]));
+    }
+    start.elapsed() / total as u32
+}
+
+fn main() {
+    let interner: Interner<str> = Interner::with_capacity(LEN);
+    let keys: Vec<Interned<str>> = (0..LEN)
+        .map(|i| interner.intern_fmt(format_args!("key_{i}")))
+        .collect();
+
+    let mut ids: IntMap<NoHashInterned<str>, usize> = IntMap::default();
+    let insert = time(&keys, LEN, |key| {
+        ids.insert(key.into(), key.len());
+    });
+    let lookup = time(&keys, LOOKUPS, |key| {
+        black_box(ids.get(&NoHashInterned(key)));
+    });
+    println!("IntMap:           {insert:?} per insert, {lookup:?} per lookup");
+
+    let mut default: HashMap<Interned<str>, usize> = HashMap::new();
+    let insert = time(&keys, LEN, |key| {
+        default.insert(key, key.len());
+    });
+    let lookup = time(&keys, LOOKUPS, |key| {
+        black_box(default.get(&key));
+    });
+    println!("default hasher:   {insert:?} per insert, {lookup:?} per lookup");
+}
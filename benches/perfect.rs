@@ -0,0 +1,29 @@
+//! Measures lookups in interners frozen into a perfect hash table, whose time should not depend
+//! on the number of values. Large tables still get slower once they no longer fit in the cache.
+//!
+//! Run with `cargo bench --bench perfect`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use labels::intern::Interner;
+
+const LOOKUPS: usize = 1_000_000;
+
+fn main() {
+    for len in [100, 10_000, 1_000_000] {
+        let keys: Vec<String> = (0..len).map(|i| format!("value_{i}")).collect();
+        let interner: Interner<str> = Interner::with_capacity(len);
+        for key in &keys {
+            let _ = interner.intern(key);
+        }
+        interner.freeze_perfect();
+
+        let start = Instant::now();
+        for i in 0..LOOKUPS {
+            black_box(interner.get(black_box(&keys[i % len])));
+        }
+        let per_lookup = start.elapsed() / LOOKUPS as u32;
+        println!("{len:>9} values: {per_lookup:?} per lookup");
+    }
+}
@@ -0,0 +1,60 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, DeriveInput, Expr, GenericParam, ItemImpl};
+
+/// Derives `Internable` for a sized type by implementing `InternByClone` for it, so values are
+/// cloned into leaked boxes and compared by address once interned.
+///
+/// The type must implement `Clone`, `Hash` and `Eq`, and all its generic parameters must be
+/// `'static`.
+#[proc_macro_derive(Internable)]
+pub fn derive_internable(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as DeriveInput);
+    for param in &mut input.generics.params {
+        match param {
+            GenericParam::Type(param) => param.bounds.push(parse_quote!('static)),
+            GenericParam::Lifetime(param) => param.bounds.push(parse_quote!('static)),
+            GenericParam::Const(_) => {}
+        }
+    }
+    let where_clause = input.generics.make_where_clause();
+    where_clause.predicates.push(parse_quote!(
+        Self: ::std::clone::Clone + ::std::hash::Hash + ::std::cmp::Eq
+    ));
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::labels::intern::InternByClone for #name #ty_generics #where_clause {}
+    }
+    .into()
+}
+
+/// Declares the parent of a label type in a label hierarchy, placed on its `impl MyLabel for Type`
+/// block: `#[labels::parent(Root)]`.
+///
+/// The argument is a label of the same trait, which is interned and registered as the parent when
+/// a label of the type is first interned. The `parent` and `is_descendant_of` methods generated by
+/// `define_label!` with the `hierarchy` flag follow these parents.
+#[proc_macro_attribute]
+pub fn parent(args: TokenStream, input: TokenStream) -> TokenStream {
+    let parent = parse_macro_input!(args as Expr);
+    let mut input = parse_macro_input!(input as ItemImpl);
+    let Some((None, label, _)) = &input.trait_ else {
+        return syn::Error::new_spanned(
+            &input.self_ty,
+            "`parent` must be placed on an `impl MyLabel for Type` block",
+        )
+        .into_compile_error()
+        .into();
+    };
+    let label = label.clone();
+    input.items.push(parse_quote! {
+        fn __label_parent(
+            &self,
+        ) -> ::std::option::Option<::labels::intern::Interned<dyn #label>> {
+            ::std::option::Option::Some(#label::intern(&(#parent)))
+        }
+    });
+    quote!(#input).into()
+}
@@ -1,75 +1,1931 @@
+use std::alloc::Layout;
+use std::any::{Any, TypeId};
 use std::borrow::Borrow;
-use std::collections::HashSet;
-use std::fmt::Debug;
-use std::hash::{Hash, Hasher};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::error::Error;
+use std::ffi::{CStr, CString, OsStr, OsString};
+use std::fmt::{Debug, Display, Pointer};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::io::{self, Write};
 use std::ops::Deref;
-use std::sync::{OnceLock, PoisonError, RwLock};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicBool, AtomicUsize};
+use std::sync::{Arc, OnceLock};
+
+use arc_swap::ArcSwapOption;
+pub use hashbrown::Equivalent;
+use hashbrown::HashTable;
+
+#[cfg(feature = "rkyv")]
+mod archive;
+mod arena;
+mod case_insensitive;
+mod erased;
+mod evicting;
+mod gc;
+#[cfg(feature = "kstring")]
+mod kstring;
+mod local;
+mod maybe;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "nohash-hasher")]
+mod nohash;
+#[cfg(feature = "rayon")]
+mod par;
+mod parents;
+mod perfect;
+mod provenance;
+mod rc;
+#[cfg(feature = "dylib-registry")]
+mod registry;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod sharded;
+#[cfg(feature = "serde")]
+mod string_table;
+mod sync;
+#[cfg(feature = "thread-local-cache")]
+mod thread_cache;
+mod ttl;
+
+#[cfg(feature = "rkyv")]
+pub use archive::ArchivableInterned;
+pub use arena::Arena;
+pub use case_insensitive::CaseInsensitiveInterner;
+pub use erased::ErasedInterned;
+pub use evicting::EvictingInterner;
+pub use gc::{GcInterned, GcInterner};
+#[cfg(feature = "derive")]
+pub use labels_derive::Internable;
+pub use local::{LocalInterned, LocalInterner};
+pub use maybe::MaybeInterned;
+#[cfg(feature = "mmap")]
+pub use mmap::MmapStringInterner;
+#[cfg(feature = "nohash-hasher")]
+pub use nohash::NoHashInterned;
+#[doc(hidden)]
+pub use parents::LabelParents;
+use perfect::PerfectTable;
+pub use provenance::InternerId;
+pub use rc::{RcInterner, Shared};
+#[cfg(feature = "dylib-registry")]
+pub use registry::global_interner;
+pub use sharded::{ShardedInterner, ShardedStats};
+#[cfg(feature = "serde")]
+pub use string_table::{serialize_as_table_id, SerializeWithTable};
+use sync::{RwLock, RwLockWriteGuard};
+pub use ttl::TtlInterner;
 
 /// A trait for internable values.
 pub trait Internable: Hash + Eq {
     /// Creates a static reference to `self`, possibly leaking memory.
     fn leak(&self) -> &'static Self;
 
-    /// Returns `true` if the two references point to the same value.
-    fn ref_eq(&self, other: &Self) -> bool;
+    /// Like [`Internable::leak`], but may allocate the copy of `self` in `arena`.
+    fn leak_in(&self, arena: &Arena) -> &'static Self {
+        let _ = arena;
+        self.leak()
+    }
+
+    /// Returns `true` if the two references point to the same value.
+    fn ref_eq(&self, other: &Self) -> bool;
+
+    /// Feeds the reference to the hasher.
+    fn ref_hash<H: Hasher>(&self, state: &mut H);
+
+    /// Returns the [`TypeId`] of the concrete type of `self`, which trait objects should
+    /// forward to the underlying value.
+    fn value_type_id(&self) -> TypeId
+    where
+        Self: 'static,
+    {
+        Any::type_id(self)
+    }
+
+    /// Formats `self` for the diagnostics of an interner. `Debug` is not required of internable
+    /// values, so this defaults to the name of the type.
+    fn fmt_debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(std::any::type_name::<Self>())
+    }
+}
+
+/// A type whose values can be copied into a [`Box`], required by the interners which own their
+/// values instead of leaking them, such as [`RcInterner`].
+pub trait ToBoxed {
+    /// Creates an owned copy of `self`.
+    fn to_boxed(&self) -> Box<Self>;
+}
+
+/// An internable type with a process-wide interner, for interning where no interner can be
+/// passed along, such as when deserializing an [`Interned<T>`].
+pub trait GlobalInternable: Internable + 'static {
+    /// Returns the process-wide interner of the type.
+    fn global_interner() -> &'static Interner<Self>;
+}
+
+/// The process-wide interner of strings.
+static GLOBAL_STRINGS: Interner<str> = Interner::new();
+
+impl GlobalInternable for str {
+    fn global_interner() -> &'static Interner<Self> {
+        &GLOBAL_STRINGS
+    }
+}
+
+/// Mixes the bits of an address into a well distributed hash.
+///
+/// The low bits of addresses are mostly zero because of alignment, which hurts the bucket
+/// distribution of hashers that do little mixing of their own.
+#[doc(hidden)]
+pub fn mix_address(ptr: *const ()) -> u64 {
+    // The 64-bit finalizer of MurmurHash3.
+    let mut x = ptr.addr() as u64;
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    x ^ (x >> 33)
+}
+
+impl Internable for str {
+    fn leak(&self) -> &'static Self {
+        if self.is_empty() {
+            return "";
+        }
+        let str = self.to_owned().into_boxed_str();
+        Box::leak(str)
+    }
+
+    fn fmt_debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+
+    fn leak_in(&self, arena: &Arena) -> &'static Self {
+        if self.is_empty() {
+            return "";
+        }
+        arena.alloc_str(self)
+    }
+
+    // The address of an empty string is arbitrary, so all empty strings are considered the same.
+    fn ref_eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && (self.is_empty() || self.as_ptr() == other.as_ptr())
+    }
+
+    fn ref_hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        if !self.is_empty() {
+            state.write_u64(mix_address(self.as_ptr().cast()));
+        }
+    }
+}
+
+impl Internable for [u8] {
+    fn leak(&self) -> &'static Self {
+        if self.is_empty() {
+            return &[];
+        }
+        Box::leak(self.into())
+    }
+
+    fn fmt_debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+
+    fn leak_in(&self, arena: &Arena) -> &'static Self {
+        if self.is_empty() {
+            return &[];
+        }
+        arena.alloc_bytes(self)
+    }
+
+    // Like empty strings, all empty byte strings are considered the same.
+    fn ref_eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && (self.is_empty() || self.as_ptr() == other.as_ptr())
+    }
+
+    fn ref_hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        if !self.is_empty() {
+            state.write_u64(mix_address(self.as_ptr().cast()));
+        }
+    }
+}
+
+impl Internable for OsStr {
+    fn leak(&self) -> &'static Self {
+        // SAFETY: the bytes are an unchanged copy of the encoded bytes of an `OsStr`.
+        unsafe { OsStr::from_encoded_bytes_unchecked(self.as_encoded_bytes().leak()) }
+    }
+
+    fn fmt_debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+
+    fn leak_in(&self, arena: &Arena) -> &'static Self {
+        // SAFETY: see `leak`.
+        unsafe { OsStr::from_encoded_bytes_unchecked(self.as_encoded_bytes().leak_in(arena)) }
+    }
+
+    fn ref_eq(&self, other: &Self) -> bool {
+        self.as_encoded_bytes().ref_eq(other.as_encoded_bytes())
+    }
+
+    fn ref_hash<H: Hasher>(&self, state: &mut H) {
+        self.as_encoded_bytes().ref_hash(state);
+    }
+}
+
+impl Internable for Path {
+    fn leak(&self) -> &'static Self {
+        Path::new(self.as_os_str().leak())
+    }
+
+    fn fmt_debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+
+    fn leak_in(&self, arena: &Arena) -> &'static Self {
+        Path::new(self.as_os_str().leak_in(arena))
+    }
+
+    fn ref_eq(&self, other: &Self) -> bool {
+        self.as_os_str().ref_eq(other.as_os_str())
+    }
+
+    fn ref_hash<H: Hasher>(&self, state: &mut H) {
+        self.as_os_str().ref_hash(state);
+    }
+}
+
+impl Internable for CStr {
+    fn leak(&self) -> &'static Self {
+        // SAFETY: the bytes are an unchanged copy of the bytes of a `CStr`.
+        unsafe { CStr::from_bytes_with_nul_unchecked(self.to_bytes_with_nul().leak()) }
+    }
+
+    fn fmt_debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+
+    fn leak_in(&self, arena: &Arena) -> &'static Self {
+        // SAFETY: see `leak`.
+        unsafe { CStr::from_bytes_with_nul_unchecked(self.to_bytes_with_nul().leak_in(arena)) }
+    }
+
+    fn ref_eq(&self, other: &Self) -> bool {
+        self.as_ptr() == other.as_ptr()
+    }
+
+    fn ref_hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(mix_address(self.as_ptr().cast()));
+    }
+}
+
+/// The size of the stack buffer of [`Interner::intern_fmt`].
+const FMT_BUF_LEN: usize = 256;
+
+/// A stack buffer for formatting, which moves its contents to the heap once it is full.
+struct FmtBuf {
+    buf: [u8; FMT_BUF_LEN],
+    len: usize,
+    /// The formatted string once it outgrew `buf`, empty before.
+    spilled: String,
+}
+
+impl FmtBuf {
+    fn new() -> Self {
+        FmtBuf {
+            buf: [0; FMT_BUF_LEN],
+            len: 0,
+            spilled: String::new(),
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.buf[..self.len]).expect("only strings are written")
+    }
+
+    fn is_spilled(&self) -> bool {
+        self.spilled.capacity() != 0
+    }
+}
+
+impl std::fmt::Write for FmtBuf {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        if self.is_spilled() {
+            self.spilled.push_str(s);
+            return Ok(());
+        }
+        let end = self.len + s.len();
+        match self.buf.get_mut(self.len..end) {
+            Some(buf) => {
+                buf.copy_from_slice(s.as_bytes());
+                self.len = end;
+            }
+            None => {
+                let mut spilled = String::with_capacity(end.max(2 * FMT_BUF_LEN));
+                spilled.push_str(self.as_str());
+                spilled.push_str(s);
+                self.spilled = spilled;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Opts a sized type into [`Internable`] by cloning its values into leaked boxes, with an empty
+/// `impl InternByClone for MyType {}`.
+pub trait InternByClone: Clone + Hash + Eq + 'static {}
+
+impl<T: InternByClone> Internable for T {
+    fn leak(&self) -> &'static Self {
+        Box::leak(Box::new(self.clone()))
+    }
+
+    fn ref_eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+
+    fn ref_hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(mix_address((self as *const Self).cast()));
+    }
+}
+
+/// Implements [`InternByClone`] for primitive and owned standard library types, so they and
+/// slices of them are internable. `u8` is left out, since `[u8]` is internable like `str`.
+macro_rules! impl_intern_by_clone {
+    ($($ty:ty),*) => {
+        $(impl InternByClone for $ty {})*
+    };
+}
+
+impl_intern_by_clone!(u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, bool, char);
+impl_intern_by_clone!((), String, Box<str>, Arc<str>, PathBuf, OsString, CString);
+
+impl<T: Internable + ?Sized> InternByClone for Interned<T> {}
+
+impl<T: Clone + Hash + Eq + 'static> InternByClone for Vec<T> {}
+
+impl<T: Clone + Hash + Eq + 'static> InternByClone for Box<[T]> {}
+
+impl<T: Clone + Hash + Eq + 'static> InternByClone for Option<T> {}
+
+/// Implements [`InternByClone`] for tuples of up to twelve elements, so composite keys such as
+/// `(Interned<str>, u32)` are internable.
+macro_rules! impl_intern_by_clone_tuples {
+    () => {};
+    ($first:ident $($rest:ident)*) => {
+        impl<$first, $($rest),*> InternByClone for ($first, $($rest,)*)
+        where
+            $first: Clone + Hash + Eq + 'static,
+            $($rest: Clone + Hash + Eq + 'static,)*
+        {
+        }
+
+        impl_intern_by_clone_tuples!($($rest)*);
+    };
+}
+
+impl_intern_by_clone_tuples!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11);
+
+impl<T: InternByClone> Internable for [T] {
+    fn leak(&self) -> &'static Self {
+        Box::leak(self.into())
+    }
+
+    // The address of an empty slice is arbitrary, so all empty slices are considered the same.
+    fn ref_eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && (self.is_empty() || self.as_ptr() == other.as_ptr())
+    }
+
+    fn ref_hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        if !self.is_empty() {
+            state.write_u64(mix_address(self.as_ptr().cast()));
+        }
+    }
+}
+
+/// Implements [`ToBoxed`] for unsized types which can be boxed with `From<&T>`.
+macro_rules! impl_to_boxed {
+    ($($ty:ty),*) => {
+        $(impl ToBoxed for $ty {
+            fn to_boxed(&self) -> Box<Self> {
+                self.into()
+            }
+        })*
+    };
+}
+
+impl_to_boxed!(str, [u8], OsStr, Path, CStr);
+
+impl<T: InternByClone> ToBoxed for T {
+    fn to_boxed(&self) -> Box<Self> {
+        Box::new(self.clone())
+    }
+}
+
+impl<T: InternByClone> ToBoxed for [T] {
+    fn to_boxed(&self) -> Box<Self> {
+        self.into()
+    }
+}
+
+/// An interned value. Will stay valid until the end of the program and will not drop.
+///
+/// Equality and hashing use the address of the value rather than the value itself, so interned
+/// values are cheap keys for hash maps, including insertion ordered ones such as `IndexMap`.
+/// Ordering compares the values.
+pub struct Interned<T: ?Sized + 'static>(pub &'static T);
+
+#[cfg(not(feature = "fast-hash"))]
+type DefaultInner = std::hash::RandomState;
+#[cfg(feature = "fast-hash")]
+type DefaultInner = rustc_hash::FxBuildHasher;
+
+/// The default [`BuildHasher`] of an [`Interner`].
+///
+/// This hashes with SipHash through [`RandomState`](std::hash::RandomState), or with FxHash if the
+/// `fast-hash` feature is enabled. FxHash is much faster for long values but not resistant to
+/// collisions crafted by an attacker. The type is the same either way, so enabling the feature
+/// never changes the type of an interner.
+#[derive(Clone, Default)]
+pub struct DefaultHashBuilder(DefaultInner);
+
+impl Debug for DefaultHashBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DefaultHashBuilder").finish_non_exhaustive()
+    }
+}
+
+impl BuildHasher for DefaultHashBuilder {
+    type Hasher = DefaultHasher;
+
+    fn build_hasher(&self) -> DefaultHasher {
+        DefaultHasher(self.0.build_hasher())
+    }
+}
+
+/// The [`Hasher`] created by [`DefaultHashBuilder`].
+#[derive(Clone)]
+pub struct DefaultHasher(<DefaultInner as BuildHasher>::Hasher);
+
+impl Debug for DefaultHasher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DefaultHasher").finish_non_exhaustive()
+    }
+}
+
+impl Hasher for DefaultHasher {
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.write(bytes);
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.0.write_u8(i);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.0.write_u32(i);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.0.write_u64(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.0.write_usize(i);
+    }
+}
+
+/// An [`Interner`] hashing with FxHash, which is what [`DefaultHashBuilder`] uses with the
+/// `fast-hash` feature, named explicitly.
+#[cfg(feature = "fast-hash")]
+pub type FxInterner<T> = Interner<T, rustc_hash::FxBuildHasher>;
+
+/// A thread-safe interner which can be used to create [`Interned<T>`] from a `&T`.
+///
+/// Values are hashed with the [`BuildHasher`] `S`, which is created with [`Default`] when the
+/// first value is interned so that the interner can be constructed in a `static`.
+pub struct Interner<T: ?Sized + 'static, S = DefaultHashBuilder> {
+    /// Hashes the values for both `entries` and `snapshot`, so a value is hashed once per lookup.
+    hasher: OnceLock<S>,
+    entries: OnceLock<RwLock<Entries<T>>>,
+    sealed: AtomicBool,
+    perfect: OnceLock<PerfectTable<T>>,
+    /// An immutable copy of the interned values, set once the interner is frozen.
+    frozen: OnceLock<ValuesSnapshot<T>>,
+    arena: Arena,
+    /// An immutable copy of the interned values which is read without locking.
+    snapshot: ArcSwapOption<ValuesSnapshot<T>>,
+    /// The number of lookups which missed the snapshot but found the value under the lock.
+    stale_hits: AtomicUsize,
+    /// The number of lookups which found an interned value.
+    hits: AtomicUsize,
+    /// The number of lookups which found no interned value.
+    misses: AtomicUsize,
+    /// Identifies the thread-local caches of this interner, zero until the first lookup.
+    #[cfg(feature = "thread-local-cache")]
+    cache_key: std::sync::atomic::AtomicU64,
+    /// The capacity to allocate when the first value is interned.
+    capacity: usize,
+    limits: Limits,
+}
+
+/// The values held by an [`Interner`], in the order they were first interned.
+struct Entries<T: ?Sized + 'static> {
+    id: InternerId,
+    /// Indices into `values`, hashed by the value they point at.
+    indices: HashTable<usize>,
+    values: Vec<&'static T>,
+    caps: HashMap<TypeId, TypeCap>,
+    /// The total size of the values.
+    bytes: usize,
+    limits: Limits,
+}
+
+/// The limits on the growth of an [`Interner`].
+#[derive(Clone, Copy)]
+struct Limits {
+    entries: usize,
+    bytes: usize,
+}
+
+/// The limit on the number of values of one type set by [`Interner::set_per_type_cap`].
+struct TypeCap {
+    max: usize,
+    count: usize,
+}
+
+impl<T: ?Sized> Entries<T> {
+    fn new(capacity: usize, limits: Limits) -> Self {
+        Self {
+            id: InternerId::next(),
+            indices: HashTable::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+            caps: HashMap::new(),
+            bytes: 0,
+            limits,
+        }
+    }
+}
+
+impl<T: Internable + ?Sized> Entries<T> {
+    /// Returns the position of the value equivalent to `key` in `values`, given the hash of `key`.
+    fn find<Q: Equivalent<T> + ?Sized>(&self, hash: u64, key: &Q) -> Option<usize> {
+        self.indices
+            .find(hash, |&index| key.equivalent(self.values[index]))
+            .copied()
+    }
+
+    fn get_hashed(&self, hash: u64, value: &T) -> Option<&'static T> {
+        self.find(hash, value).map(|index| self.values[index])
+    }
+
+    fn insert_hashed(&mut self, hasher: &impl BuildHasher, hash: u64, value: &'static T) {
+        if let Some(cap) = self.cap_mut(value) {
+            cap.count += 1;
+        }
+        provenance::record(value, self.id);
+        self.bytes += std::mem::size_of_val(value);
+        let Self {
+            indices, values, ..
+        } = self;
+        indices.insert_unique(hash, values.len(), |&index| hasher.hash_one(values[index]));
+        values.push(value);
+        #[cfg(feature = "tracing")]
+        self.trace_insert(value);
+    }
+
+    /// Emits an event for the newly interned `value`, and another one whenever the number of
+    /// values reaches a power of two.
+    #[cfg(feature = "tracing")]
+    fn trace_insert(&self, value: &T) {
+        let type_name = std::any::type_name::<T>();
+        tracing::debug!(
+            type_name,
+            value = ?InternableDebug(value),
+            "interned a new value"
+        );
+        let len = self.values.len();
+        if len >= 1024 && len.is_power_of_two() {
+            tracing::info!(
+                type_name,
+                entries = len,
+                bytes = self.bytes,
+                "interner reached {len} values"
+            );
+        }
+    }
+
+    /// Returns an error if inserting `value` would exceed the limits of the interner or the cap of
+    /// its type.
+    fn check_insert(&self, value: &T) -> Result<(), InternError> {
+        if self.values.len() >= self.limits.entries
+            || self.bytes.saturating_add(std::mem::size_of_val(value)) > self.limits.bytes
+        {
+            return Err(InternError::LimitReached);
+        }
+        if self.caps.is_empty() {
+            return Ok(());
+        }
+        match self.caps.get(&value.value_type_id()) {
+            Some(cap) if cap.count >= cap.max => Err(InternError::TypeCapReached),
+            _ => Ok(()),
+        }
+    }
+
+    fn cap_mut(&mut self, value: &T) -> Option<&mut TypeCap> {
+        if self.caps.is_empty() {
+            return None;
+        }
+        self.caps.get_mut(&value.value_type_id())
+    }
+
+    /// Returns `true` if `snapshot` was taken from these entries and none of the values it saw
+    /// were removed since.
+    fn holds(&self, snapshot: &InternerSnapshot<T>) -> bool {
+        snapshot.id == self.id
+            && match snapshot.last {
+                Some(last) => self.values.get(snapshot.len - 1) == Some(&last),
+                None => true,
+            }
+    }
+
+    /// Removes every value after the first `len` ones.
+    #[cfg(feature = "clear")]
+    fn truncate(&mut self, hasher: &impl BuildHasher, len: usize) {
+        for index in (len..self.values.len()).rev() {
+            let value = self.values[index];
+            let hash = hasher.hash_one(value);
+            if let Ok(entry) = self.indices.find_entry(hash, |&found| found == index) {
+                entry.remove();
+            }
+            if let Some(cap) = self.cap_mut(value) {
+                cap.count -= 1;
+            }
+            self.bytes -= std::mem::size_of_val(value);
+            provenance::forget(value, self.id);
+        }
+        self.values.truncate(len);
+    }
+
+    /// Replaces the value equal to `value` in place, or inserts it if there is none.
+    fn replace(&mut self, hasher: &impl BuildHasher, value: &'static T) {
+        let hash = hasher.hash_one(value);
+        match self.find(hash, value) {
+            Some(index) => {
+                provenance::forget(self.values[index], self.id);
+                provenance::record(value, self.id);
+                self.values[index] = value;
+            }
+            None => self.insert_hashed(hasher, hash, value),
+        }
+    }
+}
+
+/// An immutable copy of the values of an [`Interner`], which is read without locking.
+///
+/// The table is a copy of the one of the entries, so it is built without hashing the values
+/// again, and it is probed with the same hashes. Returned by [`Interner::values_snapshot`].
+pub struct ValuesSnapshot<T: ?Sized + 'static> {
+    indices: HashTable<usize>,
+    values: Vec<&'static T>,
+}
+
+impl<T: ?Sized> ValuesSnapshot<T> {
+    /// Returns the number of values in the snapshot.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the snapshot holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns an iterator over the values in the snapshot, in the order they were first
+    /// interned.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Interned<T>> + '_ {
+        self.values.iter().map(|value| Interned(*value))
+    }
+}
+
+impl<T: ?Sized> Debug for ValuesSnapshot<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValuesSnapshot")
+            .field("len", &self.values.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Internable + ?Sized> ValuesSnapshot<T> {
+    fn new(entries: &Entries<T>) -> Self {
+        Self {
+            indices: entries.indices.clone(),
+            values: entries.values.clone(),
+        }
+    }
+
+    fn get_hashed(&self, hash: u64, value: &T) -> Option<&'static T> {
+        self.indices
+            .find(hash, |&index| *self.values[index] == *value)
+            .map(|&index| self.values[index])
+    }
+}
+
+/// The write locked entries of an [`Interner`] which do not hold a value yet, with the hash of
+/// that value.
+struct VacantEntry<'a, T: ?Sized + 'static, S> {
+    entries: RwLockWriteGuard<'a, Entries<T>>,
+    hasher: &'a S,
+    hash: u64,
+}
+
+impl<T: Internable + ?Sized, S: BuildHasher> VacantEntry<'_, T, S> {
+    /// Inserts `value`, which must be equal to the value this entry was looked up for.
+    fn insert(&mut self, value: &'static T) {
+        self.entries.insert_hashed(self.hasher, self.hash, value);
+    }
+}
+
+impl<T: ?Sized, S> Deref for VacantEntry<'_, T, S> {
+    type Target = Entries<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.entries
+    }
+}
+
+/// An error returned when a value could not be interned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InternError {
+    /// The interner was [sealed](Interner::seal) and the value was not interned before.
+    Sealed,
+    /// The interner holds as many values of the concrete type of the value as allowed by
+    /// [`Interner::set_per_type_cap`].
+    TypeCapReached,
+    /// The interner holds as many values or bytes as allowed by [`Interner::with_max_entries`]
+    /// or [`Interner::with_max_bytes`].
+    LimitReached,
+}
+
+impl Display for InternError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sealed => f.write_str("cannot intern a new value into a sealed interner"),
+            Self::TypeCapReached => {
+                f.write_str("the interner holds the maximum number of values of this type")
+            }
+            Self::LimitReached => f.write_str("the interner is full"),
+        }
+    }
+}
+
+impl Error for InternError {}
+
+/// An error returned when [`Interner::try_reserve`] could not reserve capacity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReserveError {
+    /// The requested capacity exceeds the maximum size of a collection.
+    CapacityOverflow,
+    /// The allocator failed to allocate memory of the given layout.
+    AllocError {
+        /// The layout of the allocation which failed.
+        layout: Layout,
+    },
+}
+
+impl Display for ReserveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CapacityOverflow => f.write_str("the requested capacity exceeds the maximum"),
+            Self::AllocError { layout } => {
+                write!(f, "failed to allocate {} bytes", layout.size())
+            }
+        }
+    }
+}
+
+impl Error for ReserveError {}
 
-    /// Feeds the reference to the hasher.
-    fn ref_hash<H: Hasher>(&self, state: &mut H);
+/// The values held by an [`Interner`] at some point, taken by [`Interner::snapshot`].
+pub struct InternerSnapshot<T: ?Sized + 'static> {
+    id: InternerId,
+    len: usize,
+    /// The last value interned at that point, to detect values removed since.
+    last: Option<&'static T>,
 }
 
-impl Internable for str {
-    fn leak(&self) -> &'static Self {
-        let str = self.to_owned().into_boxed_str();
-        Box::leak(str)
+impl<T: ?Sized> Clone for InternerSnapshot<T> {
+    fn clone(&self) -> Self {
+        *self
     }
+}
 
-    fn ref_eq(&self, other: &Self) -> bool {
-        self.as_ptr() == other.as_ptr() && self.len() == other.len()
-    }
+impl<T: ?Sized> Copy for InternerSnapshot<T> {}
 
-    fn ref_hash<H: Hasher>(&self, state: &mut H) {
-        self.len().hash(state);
-        self.as_ptr().hash(state);
+impl<T: ?Sized> Debug for InternerSnapshot<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InternerSnapshot")
+            .field("id", &self.id)
+            .field("len", &self.len)
+            .finish_non_exhaustive()
     }
 }
 
-/// An interned value. Will stay valid until the end of the program and will not drop.
-pub struct Interned<T: ?Sized + 'static>(pub &'static T);
+/// Formats an internable value with [`Internable::fmt_debug`].
+#[cfg(feature = "tracing")]
+struct InternableDebug<'a, T: ?Sized>(&'a T);
 
-/// A thread-safe interner which can be used to create [`Interned<T>`] from a `&T`.
-pub struct Interner<T: ?Sized + 'static>(OnceLock<RwLock<HashSet<&'static T>>>);
+#[cfg(feature = "tracing")]
+impl<T: Internable + ?Sized> Debug for InternableDebug<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt_debug(f)
+    }
+}
+
+/// Statistics about the values held by an [`Interner`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct InternStats {
+    /// The number of distinct values interned.
+    pub entries: usize,
+    /// The approximate number of bytes leaked for the values, not counting allocator overhead.
+    pub bytes: usize,
+    /// The number of values the interner can hold without reallocating its table.
+    pub capacity: usize,
+    /// The number of lookups which found a value that was interned before.
+    pub hits: usize,
+    /// The number of lookups which found no equal value, most of which interned a new one.
+    pub misses: usize,
+}
 
-impl<T: ?Sized> Default for Interner<T> {
+impl<T: ?Sized, S> Default for Interner<T, S> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<T: ?Sized> Interner<T> {
+impl<T: ?Sized, S> Interner<T, S> {
     pub const fn new() -> Self {
-        Self(OnceLock::new())
+        Self::with_capacity(0)
+    }
+
+    /// Creates an interner with room for at least `capacity` values, which is allocated when the
+    /// first value is interned.
+    pub const fn with_capacity(capacity: usize) -> Self {
+        Self {
+            hasher: OnceLock::new(),
+            entries: OnceLock::new(),
+            sealed: AtomicBool::new(false),
+            perfect: OnceLock::new(),
+            frozen: OnceLock::new(),
+            arena: Arena::new(),
+            snapshot: ArcSwapOption::const_empty(),
+            stale_hits: AtomicUsize::new(0),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+            #[cfg(feature = "thread-local-cache")]
+            cache_key: std::sync::atomic::AtomicU64::new(0),
+            capacity,
+            limits: Limits {
+                entries: usize::MAX,
+                bytes: usize::MAX,
+            },
+        }
+    }
+
+    /// Limits the interner to `max` values. Interning a new value past the limit fails with
+    /// [`InternError::LimitReached`].
+    pub const fn with_max_entries(mut self, max: usize) -> Self {
+        self.limits.entries = max;
+        self
+    }
+
+    /// Limits the total size of the values held by the interner to `max` bytes. Interning a new
+    /// value past the limit fails with [`InternError::LimitReached`].
+    pub const fn with_max_bytes(mut self, max: usize) -> Self {
+        self.limits.bytes = max;
+        self
+    }
+
+    /// Seals the interner. Interning a value which was not interned before will fail from now on,
+    /// while values that were already interned can still be obtained.
+    pub fn seal(&self) {
+        self.sealed.store(true, atomic::Ordering::Release);
+    }
+
+    /// Returns `true` if the interner has been [sealed](Interner::seal).
+    pub fn is_sealed(&self) -> bool {
+        self.sealed.load(atomic::Ordering::Acquire)
+    }
+
+    /// Returns the number of lookups which found an interned value and which found none, without
+    /// locking.
+    pub(super) fn lookups(&self) -> (usize, usize) {
+        (
+            self.hits.load(atomic::Ordering::Relaxed),
+            self.misses.load(atomic::Ordering::Relaxed),
+        )
+    }
+
+    /// Returns the id of this interner, as reported by [`Interned::provenance`].
+    pub fn id(&self) -> InternerId {
+        self.lock().read().id
+    }
+
+    fn lock(&self) -> &RwLock<Entries<T>> {
+        self.entries
+            .get_or_init(|| RwLock::new(Entries::new(self.capacity, self.limits)))
+    }
+
+    fn hasher(&self) -> &S
+    where
+        S: Default,
+    {
+        self.hasher.get_or_init(S::default)
     }
 }
 
-impl<T: Internable + ?Sized> Interner<T> {
+impl<T: Internable + ?Sized, S: BuildHasher + Default> Interner<T, S> {
+    /// Returns an [`Interned<T>`] equal to `value`, leaking a copy of `value` if it was not
+    /// interned before.
+    ///
+    /// Discarding the result is a mistake, so it is linted by `unused_must_use`:
+    ///
+    /// ```compile_fail
+    /// #![deny(unused_must_use)]
+    /// let interner = labels::intern::Interner::<str>::new();
+    /// interner.intern("label");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
     pub fn intern(&self, value: &T) -> Interned<T> {
-        let lock = self.0.get_or_init(Default::default);
-        {
-            let set = lock.read().unwrap_or_else(PoisonError::into_inner);
-            if let Some(value) = set.get(value) {
-                return Interned(*value);
+        match self.try_intern(value) {
+            Ok(interned) => interned,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Like [`Interner::intern`], but returns an error instead of panicking if `value` cannot be
+    /// interned.
+    pub fn try_intern(&self, value: &T) -> Result<Interned<T>, InternError> {
+        self.try_intern_inserted(value)
+            .map(|(interned, _)| interned)
+    }
+
+    /// Like [`Interner::try_intern`], but also returns `true` if `value` was not interned before.
+    pub(super) fn try_intern_inserted(
+        &self,
+        value: &T,
+    ) -> Result<(Interned<T>, bool), InternError> {
+        self.intern_or_insert(value, None, || value.leak_in(&self.arena))
+    }
+
+    /// Like [`Interner::try_intern_inserted`], but with the `hash` of `value` already computed
+    /// with the hasher of the interner.
+    pub(super) fn try_intern_inserted_hashed(
+        &self,
+        hash: u64,
+        value: &T,
+    ) -> Result<(Interned<T>, bool), InternError> {
+        self.intern_or_insert(value, Some(hash), || value.leak_in(&self.arena))
+    }
+
+    /// Like [`Interner::intern`], but also returns a hash of the content of `value`.
+    ///
+    /// The hash is computed with a [`StableHasher`](crate::StableHasher), so it is the same
+    /// across interners, runs and processes on the same platform, making it usable as a key to
+    /// coordinate on content between processes. Label trait objects hash their `TypeId`, which
+    /// is not stable between compilations; use the `stable_hash` method generated by the
+    /// `stable_hash` flag instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
+    pub fn intern_hashed(&self, value: &T) -> (Interned<T>, u64) {
+        let mut state = crate::StableHasher::new();
+        value.hash(&mut state);
+        (self.intern(value), state.finish())
+    }
+
+    /// Returns an [`Interned<T>`] equal to `value`, adopting `value` itself as the canonical
+    /// reference instead of leaking a copy if it was not interned before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
+    pub fn intern_ref(&self, value: &'static T) -> Interned<T> {
+        match self.intern_or_insert(value, None, || value) {
+            Ok((interned, _)) => interned,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Interns every value of `values` under a single acquisition of the lock, returning the
+    /// [`Interned<T>`]s in the same order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the values was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
+    pub fn intern_iter<'v>(&self, values: impl IntoIterator<Item = &'v T>) -> Vec<Interned<T>>
+    where
+        T: 'v,
+    {
+        if self.is_frozen() {
+            return values.into_iter().map(|value| self.intern(value)).collect();
+        }
+        let mut entries = self.lock().write();
+        values
+            .into_iter()
+            .map(|value| {
+                let hash = self.hasher().hash_one(value);
+                let existing = entries.get_hashed(hash, value);
+                self.count(existing.is_some());
+                if let Some(existing) = existing {
+                    return Interned(existing);
+                }
+                if self.is_sealed() {
+                    panic!("{}", InternError::Sealed);
+                }
+                if let Err(err) = entries.check_insert(value) {
+                    panic!("{err}");
+                }
+                let leaked = value.leak_in(&self.arena);
+                entries.insert_hashed(self.hasher(), hash, leaked);
+                Interned(leaked)
+            })
+            .collect()
+    }
+
+    /// Returns the [`Interned<T>`] equivalent to `key`, only calling `make` to create the value if
+    /// none was interned before. This avoids constructing values which are expensive to build,
+    /// such as formatted strings, when they are already interned.
+    ///
+    /// `key` must hash like the values it is equivalent to. `make` is called while the interner is
+    /// locked, so it must not use the interner.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value returned by `make` is not equivalent to `key`, or if no value
+    /// equivalent to `key` was interned before and the new value cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
+    pub fn intern_with<Q: Hash + Equivalent<T> + ?Sized>(
+        &self,
+        key: &Q,
+        make: impl FnOnce() -> Box<T>,
+    ) -> Interned<T> {
+        let hash = self.hasher().hash_one(key);
+        let entries = self.lock().read();
+        if let Some(index) = entries.find(hash, key) {
+            self.count(true);
+            return Interned(entries.values[index]);
+        }
+        drop(entries);
+        if self.is_frozen() {
+            self.count(false);
+            panic!("{}", InternError::Sealed);
+        }
+
+        let mut entries = self.lock().write();
+        let found = entries.find(hash, key);
+        self.count(found.is_some());
+        if let Some(index) = found {
+            return Interned(entries.values[index]);
+        }
+        if self.is_sealed() {
+            panic!("{}", InternError::Sealed);
+        }
+        let value = make();
+        assert!(
+            key.equivalent(&*value),
+            "made value must be equivalent to its key"
+        );
+        if let Err(err) = entries.check_insert(&value) {
+            panic!("{err}");
+        }
+        let leaked = Box::leak(value);
+        entries.insert_hashed(self.hasher(), hash, leaked);
+        Interned(leaked)
+    }
+
+    /// Returns an [`Interned<T>`] equal to `value`, leaking `value` itself instead of a copy if it
+    /// was not interned before. Otherwise `value` is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
+    pub fn intern_owned(&self, value: Box<T>) -> Interned<T> {
+        match self.try_intern_owned(value) {
+            Ok(interned) => interned,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Like [`Interner::intern_owned`], but returns an error instead of panicking if `value`
+    /// cannot be interned, see [`Interner::try_intern`].
+    pub fn try_intern_owned(&self, value: Box<T>) -> Result<Interned<T>, InternError> {
+        match self.get_or_lock(&value, None) {
+            Ok(value) => Ok(Interned(value)),
+            Err(Some(mut entries)) if !self.is_sealed() => {
+                entries.check_insert(&value)?;
+                let leaked = Box::leak(value);
+                entries.insert(leaked);
+                Ok(Interned(leaked))
+            }
+            Err(_) => Err(InternError::Sealed),
+        }
+    }
+
+    /// Like [`Interner::intern_owned`], but keeps the allocation of `value` alive instead of a
+    /// copy if it was not interned before, so values already shared through an [`Arc`] are not
+    /// copied. Otherwise `value` is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
+    pub fn intern_arc(&self, value: Arc<T>) -> Interned<T> {
+        match self.get_or_lock(&value, None) {
+            Ok(value) => Interned(value),
+            Err(Some(mut entries)) if !self.is_sealed() => {
+                if let Err(err) = entries.check_insert(&value) {
+                    panic!("{err}");
+                }
+                // SAFETY: the reference count of the value is never decremented again, so it is
+                // never freed.
+                let leaked = unsafe { &*Arc::into_raw(value) };
+                entries.insert(leaked);
+                Interned(leaked)
+            }
+            Err(_) => panic!("{}", InternError::Sealed),
+        }
+    }
+
+    /// Interns `value`, adopting the canonical reference held by `authoritative` if it contains an
+    /// equal value, and interning locally otherwise.
+    ///
+    /// This allows gradually consolidating values onto one canonical interner. If `self` already
+    /// holds an equal value, that one is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
+    pub fn intern_preferring(&self, value: &T, authoritative: &Interner<T, S>) -> Interned<T> {
+        match authoritative.lookup(value) {
+            Some(canonical) => self.intern_ref(canonical),
+            None => self.intern(value),
+        }
+    }
+
+    /// Returns an [`Interned<T>`] equal to `value`, only interning it if it was not interned
+    /// before and `predicate` accepts the current statistics of the interner.
+    ///
+    /// The predicate is evaluated under the write lock, so no other value is interned
+    /// concurrently. Returns `None` if the predicate denied the value or the value cannot be
+    /// interned, see [`Interner::try_intern`].
+    pub fn intern_if(
+        &self,
+        value: &T,
+        predicate: impl FnOnce(&InternStats) -> bool,
+    ) -> Option<Interned<T>> {
+        match self.get_or_lock(value, None) {
+            Ok(value) => Some(Interned(value)),
+            Err(None) => None,
+            Err(Some(entries))
+                if self.is_sealed()
+                    || entries.check_insert(value).is_err()
+                    || !predicate(&self.stats_of(&entries)) =>
+            {
+                None
+            }
+            Err(Some(mut entries)) => {
+                let leaked = value.leak_in(&self.arena);
+                entries.insert(leaked);
+                Some(Interned(leaked))
+            }
+        }
+    }
+
+    /// Returns the [`Interned<T>`] equal to `value` if it was interned before, without interning
+    /// it otherwise.
+    pub fn get(&self, value: &T) -> Option<Interned<T>> {
+        self.lookup(value).map(Interned)
+    }
+
+    /// Returns `true` if a value equal to `value` was interned before, without interning it
+    /// otherwise.
+    pub fn contains(&self, value: &T) -> bool {
+        self.lookup(value).is_some()
+    }
+
+    /// Returns every interned value in the order it was first interned.
+    pub fn to_vec(&self) -> Vec<Interned<T>> {
+        let entries = self.lock().read();
+        entries
+            .values
+            .iter()
+            .map(|value| Interned(*value))
+            .collect()
+    }
+
+    /// Returns an iterator over every interned value in the order it was first interned.
+    ///
+    /// The lock is only held while advancing the iterator, so values can be interned during the
+    /// iteration, and those are yielded as well.
+    pub fn iter(&self) -> impl Iterator<Item = Interned<T>> + '_ {
+        (0..).map_while(|index| self.lock().read().values.get(index).copied().map(Interned))
+    }
+
+    /// Returns the number of distinct values interned.
+    pub fn len(&self) -> usize {
+        self.lock().read().values.len()
+    }
+
+    /// Returns `true` if no value has been interned.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the position of `value` in the order values were first interned, or `None` if it
+    /// was never interned.
+    pub fn first_intern_index(&self, value: &T) -> Option<usize> {
+        let index = self.__first_intern_index_uncounted(value);
+        self.count(index.is_some());
+        index
+    }
+
+    /// Like [`Interner::first_intern_index`], but not counted in the [statistics](Interner::stats),
+    /// for comparisons which look values up as a side effect, such as sorting labels.
+    #[doc(hidden)]
+    pub fn __first_intern_index_uncounted(&self, value: &T) -> Option<usize> {
+        let hash = self.hasher().hash_one(value);
+        self.lock().read().find(hash, value)
+    }
+
+    /// Returns the dense index assigned to `value` when it was interned into this interner, or
+    /// `None` if it was interned into another one.
+    ///
+    /// Indices count up from zero in the order values were first interned, so they can index
+    /// `Vec` side tables and bitsets instead of hash maps. They only change when values are
+    /// removed from the interner.
+    ///
+    /// [`Interned<T>`] is a bare `&'static T`, so it cannot carry its index. This looks `value` up
+    /// again, hashing it and taking the read lock. Use [`Interner::intern_indexed`] to get the
+    /// index when interning the value instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index of `value` does not fit in a `u32`.
+    pub fn index(&self, value: Interned<T>) -> Option<u32> {
+        let hash = self.hasher().hash_one(value.0);
+        let entries = self.lock().read();
+        let index = entries.find(hash, value.0)?;
+        entries.values[index]
+            .ref_eq(value.0)
+            .then(|| u32::try_from(index).expect("interned more than `u32::MAX` values"))
+    }
+
+    /// Like [`Interner::intern`], but also returns the [index](Interner::index) of the value,
+    /// without looking it up again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`], or if its index does not fit in a `u32`.
+    #[must_use]
+    pub fn intern_indexed(&self, value: &T) -> (Interned<T>, u32) {
+        match self.try_intern_indexed(value) {
+            Ok(indexed) => indexed,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Like [`Interner::try_intern`], but also returns the [index](Interner::index) of the value,
+    /// without looking it up again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index of `value` does not fit in a `u32`.
+    pub fn try_intern_indexed(&self, value: &T) -> Result<(Interned<T>, u32), InternError> {
+        let to_u32 = |index| u32::try_from(index).expect("interned more than `u32::MAX` values");
+        let hash = self.hasher().hash_one(value);
+        let entries = self.lock().read();
+        if let Some(index) = entries.find(hash, value) {
+            self.count(true);
+            return Ok((Interned(entries.values[index]), to_u32(index)));
+        }
+        drop(entries);
+        if self.is_frozen() {
+            self.count(false);
+            return Err(InternError::Sealed);
+        }
+        let mut entries = self.lock().write();
+        let found = entries.find(hash, value);
+        self.count(found.is_some());
+        if let Some(index) = found {
+            return Ok((Interned(entries.values[index]), to_u32(index)));
+        }
+        if self.is_sealed() {
+            return Err(InternError::Sealed);
+        }
+        entries.check_insert(value)?;
+        let index = to_u32(entries.values.len());
+        let leaked = value.leak_in(&self.arena);
+        entries.insert_hashed(self.hasher(), hash, leaked);
+        Ok((Interned(leaked), index))
+    }
+
+    /// Like [`Interner::get`], but also returns the [index](Interner::index) of the value,
+    /// without looking it up again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index of `value` does not fit in a `u32`.
+    pub fn get_indexed(&self, value: &T) -> Option<(Interned<T>, u32)> {
+        let hash = self.hasher().hash_one(value);
+        let entries = self.lock().read();
+        let found = entries.find(hash, value);
+        self.count(found.is_some());
+        found.map(|index| {
+            let to_u32 = u32::try_from(index).expect("interned more than `u32::MAX` values");
+            (Interned(entries.values[index]), to_u32)
+        })
+    }
+
+    /// Returns the value with the given [index](Interner::index), if there is one.
+    pub fn from_index(&self, index: u32) -> Option<Interned<T>> {
+        let entries = self.lock().read();
+        entries
+            .values
+            .get(index as usize)
+            .map(|value| Interned(*value))
+    }
+
+    /// Seals the interner and copies the interned values into an immutable table.
+    ///
+    /// Afterwards, looking up a value never locks, whether it was interned or not. Interning a
+    /// value which was not interned before fails with [`InternError::Sealed`]. Calling this again
+    /// has no effect.
+    pub fn freeze(&self) {
+        self.seal();
+        self.frozen
+            .get_or_init(|| ValuesSnapshot::new(&self.lock().read()));
+        self.snapshot.store(None);
+    }
+
+    /// Like [`Interner::freeze`], but builds a minimal perfect hash table over the interned
+    /// values.
+    ///
+    /// Afterwards, looking up a value takes a single hash and array index, without probing or
+    /// locking. Calling this again has no effect.
+    ///
+    /// If no perfect hash table separates the values, such as when two of them feed the same data
+    /// to the hasher, this falls back to [`Interner::freeze`].
+    pub fn freeze_perfect(&self) {
+        self.seal();
+        if self.perfect.get().is_none() {
+            let entries = self.lock().read();
+            match PerfectTable::new(&entries.values) {
+                Some(table) => {
+                    let _ = self.perfect.set(table);
+                }
+                None => {
+                    self.frozen.get_or_init(|| ValuesSnapshot::new(&entries));
+                }
+            }
+        }
+        self.snapshot.store(None);
+    }
+
+    /// Returns `true` if the interner has been [frozen](Interner::freeze) by either method.
+    pub fn is_frozen(&self) -> bool {
+        self.perfect.get().is_some() || self.frozen.get().is_some()
+    }
+
+    /// Captures the values held by the interner, to [compare](Interner::diff) the interner to
+    /// them later, or roll it back to them with `restore`, which needs the `clear` feature.
+    pub fn snapshot(&self) -> InternerSnapshot<T> {
+        let entries = self.lock().read();
+        InternerSnapshot {
+            id: entries.id,
+            len: entries.values.len(),
+            last: entries.values.last().copied(),
+        }
+    }
+
+    /// Returns an immutable copy of the values held by the interner, in the order they were first
+    /// interned.
+    ///
+    /// Unlike iterating under the lock, the copy can be iterated for as long as needed without
+    /// blocking threads interning new values, which do not show up in it. The copy is shared with
+    /// lookups and only rebuilt when values were interned since it was taken.
+    pub fn values_snapshot(&self) -> Arc<ValuesSnapshot<T>> {
+        let entries = self.lock().read();
+        if let Some(snapshot) = self.snapshot.load_full() {
+            if snapshot.values.len() == entries.values.len() {
+                return snapshot;
             }
         }
+        let snapshot = Arc::new(ValuesSnapshot::new(&entries));
+        self.snapshot.store(Some(snapshot.clone()));
+        snapshot
+    }
+
+    /// Returns every value interned since `snapshot` was taken, in the order it was first
+    /// interned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `snapshot` was taken from another interner, or values it saw have been removed
+    /// since.
+    pub fn diff(&self, snapshot: &InternerSnapshot<T>) -> Vec<Interned<T>> {
+        let entries = self.lock().read();
+        assert!(
+            entries.holds(snapshot),
+            "snapshot does not match the values of the interner"
+        );
+        entries.values[snapshot.len..]
+            .iter()
+            .map(|value| Interned(*value))
+            .collect()
+    }
+
+    /// Removes every value interned since `snapshot` was taken.
+    ///
+    /// Interned values are leaked, so existing [`Interned`] handles to removed values stay valid,
+    /// but they no longer compare equal to handles for equal values interned after the call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `snapshot` was taken from another interner, values it saw have been removed
+    /// since, or the interner is [frozen](Interner::freeze).
+    #[cfg(feature = "clear")]
+    pub fn restore(&self, snapshot: &InternerSnapshot<T>) {
+        assert!(!self.is_frozen(), "cannot restore a frozen interner");
+        let mut entries = self.lock().write();
+        assert!(
+            entries.holds(snapshot),
+            "snapshot does not match the values of the interner"
+        );
+        entries.truncate(self.hasher(), snapshot.len);
+        self.snapshot.store(None);
+        #[cfg(feature = "thread-local-cache")]
+        self.invalidate_cache();
+    }
+
+    /// Returns statistics about the values held by the interner and the lookups made so far.
+    pub fn stats(&self) -> InternStats {
+        let entries = self.lock().read();
+        self.stats_of(&entries)
+    }
+
+    fn stats_of(&self, entries: &Entries<T>) -> InternStats {
+        InternStats {
+            entries: entries.values.len(),
+            bytes: entries.bytes,
+            capacity: entries.indices.capacity(),
+            hits: self.hits.load(atomic::Ordering::Relaxed),
+            misses: self.misses.load(atomic::Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the canonical reference for `value` without inserting it.
+    fn lookup(&self, value: &T) -> Option<&'static T> {
+        self.probe(value, None).ok()
+    }
+
+    /// Returns the canonical reference for `value`, or the hash of `value` if there is none.
+    ///
+    /// `hash` is the hash of `value` with the hasher of the interner, if it is already known.
+    /// Interners [frozen](Interner::freeze_perfect) into a perfect hash table do not hash `value`
+    /// with the hasher of the interner, so they return no hash.
+    fn probe(&self, value: &T, hash: Option<u64>) -> Result<&'static T, Option<u64>> {
+        let found = self.probe_uncounted(value, hash);
+        self.count(found.is_ok());
+        found
+    }
+
+    /// Counts a lookup in the [statistics](Interner::stats), as a hit if it `found` a value.
+    fn count(&self, found: bool) {
+        let counter = if found { &self.hits } else { &self.misses };
+        counter.fetch_add(1, atomic::Ordering::Relaxed);
+    }
+
+    fn probe_uncounted(&self, value: &T, hash: Option<u64>) -> Result<&'static T, Option<u64>> {
+        if let Some(perfect) = self.perfect.get() {
+            return perfect.get(value).ok_or(None);
+        }
+        let hash = || hash.unwrap_or_else(|| self.hasher().hash_one(value));
+        if let Some(frozen) = self.frozen.get() {
+            let hash = hash();
+            return frozen.get_hashed(hash, value).ok_or(Some(hash));
+        }
+        #[cfg(feature = "thread-local-cache")]
+        let cache_key = self.cache_key();
+        #[cfg(feature = "thread-local-cache")]
+        if let Some(value) = self.cached(cache_key, value) {
+            return Ok(value);
+        }
+        let hash = hash();
+        let snapshot = self.snapshot.load();
+        if let Some(value) = snapshot
+            .as_deref()
+            .and_then(|snapshot| snapshot.get_hashed(hash, value))
         {
-            let mut set = lock.write().unwrap_or_else(PoisonError::into_inner);
-            if let Some(value) = set.get(value) {
-                Interned(*value)
-            } else {
-                let leaked = value.leak();
-                set.insert(leaked);
+            #[cfg(feature = "thread-local-cache")]
+            self.cache(cache_key, value);
+            return Ok(value);
+        }
+        let entries = self.lock().read();
+        let found = entries.get_hashed(hash, value);
+        // Republish the snapshot once the lookups it missed cost as much as copying it.
+        let snapshot_len = snapshot
+            .as_deref()
+            .map_or(0, |snapshot| snapshot.values.len());
+        if found.is_some()
+            && self.stale_hits.fetch_add(1, atomic::Ordering::Relaxed) >= snapshot_len
+        {
+            self.stale_hits.store(0, atomic::Ordering::Relaxed);
+            self.snapshot
+                .store(Some(Arc::new(ValuesSnapshot::new(&entries))));
+        }
+        #[cfg(feature = "thread-local-cache")]
+        if let Some(found) = found {
+            self.cache(cache_key, found);
+        }
+        found.ok_or(Some(hash))
+    }
+
+    /// Returns the canonical reference for `value`, or the write locked entries if there is none.
+    /// A frozen interner holds no entries to insert into, so its misses return `None` without
+    /// locking.
+    ///
+    /// `value` is hashed once, for both probes and the insertion into the returned entry, unless
+    /// its `hash` is already known.
+    fn get_or_lock(
+        &self,
+        value: &T,
+        hash: Option<u64>,
+    ) -> Result<&'static T, Option<VacantEntry<'_, T, S>>> {
+        let hash = match self.probe(value, hash) {
+            Ok(value) => return Ok(value),
+            Err(_) if self.is_frozen() => return Err(None),
+            Err(hash) => hash,
+        };
+        let hasher = self.hasher();
+        let hash = hash.unwrap_or_else(|| hasher.hash_one(value));
+        let entries = self.lock().write();
+        match entries.get_hashed(hash, value) {
+            Some(value) => Ok(value),
+            None => Err(Some(VacantEntry {
+                entries,
+                hasher,
+                hash,
+            })),
+        }
+    }
+
+    /// Returns the canonical reference for `value`, inserting the one returned by `insert` if
+    /// there is none yet.
+    fn intern_or_insert(
+        &self,
+        value: &T,
+        hash: Option<u64>,
+        insert: impl FnOnce() -> &'static T,
+    ) -> Result<(Interned<T>, bool), InternError> {
+        match self.get_or_lock(value, hash) {
+            Ok(value) => Ok((Interned(value), false)),
+            Err(Some(mut entries)) if !self.is_sealed() => {
+                entries.check_insert(value)?;
+                let inserted = insert();
+                entries.insert(inserted);
+                Ok((Interned(inserted), true))
+            }
+            Err(_) => Err(InternError::Sealed),
+        }
+    }
+
+    /// Limits the number of values whose concrete type is `type_id` to `max`, so one type cannot
+    /// flood an interner shared by many types, such as the interner of a label trait.
+    ///
+    /// Interning a new value of that type past the cap fails with
+    /// [`InternError::TypeCapReached`], while values that were already interned can still be
+    /// obtained.
+    pub fn set_per_type_cap(&self, type_id: TypeId, max: usize) {
+        let mut entries = self.lock().write();
+        let count = entries
+            .values
+            .iter()
+            .filter(|value| value.value_type_id() == type_id)
+            .count();
+        entries.caps.insert(type_id, TypeCap { max, count });
+    }
+
+    /// Removes every value from the interner, so tests can start from a clean state.
+    ///
+    /// Interned values are leaked, so existing [`Interned`] handles stay valid, but they no longer
+    /// compare equal to handles for equal values interned after the call. The interner stays
+    /// [sealed](Interner::seal) if it was.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the interner is [frozen](Interner::freeze).
+    #[cfg(feature = "clear")]
+    pub fn clear(&self) {
+        assert!(!self.is_frozen(), "cannot clear a frozen interner");
+        let mut entries = self.lock().write();
+        if cfg!(debug_assertions) {
+            for value in &entries.values {
+                provenance::forget(*value, entries.id);
+            }
+        }
+        entries.indices.clear();
+        entries.values.clear();
+        entries.bytes = 0;
+        for cap in entries.caps.values_mut() {
+            cap.count = 0;
+        }
+        self.snapshot.store(None);
+        self.stale_hits.store(0, atomic::Ordering::Relaxed);
+        self.hits.store(0, atomic::Ordering::Relaxed);
+        self.misses.store(0, atomic::Ordering::Relaxed);
+        #[cfg(feature = "thread-local-cache")]
+        self.invalidate_cache();
+    }
+
+    /// Reserves capacity for at least `additional` more values.
+    pub fn reserve(&self, additional: usize) {
+        let hasher = self.hasher();
+        let mut entries = self.lock().write();
+        let Entries {
+            indices, values, ..
+        } = &mut *entries;
+        indices.reserve(additional, |&index| hasher.hash_one(values[index]));
+        values.reserve(additional);
+    }
+
+    /// Tries to reserve capacity for at least `additional` more values, returning an error instead
+    /// of aborting if the allocation fails.
+    pub fn try_reserve(&self, additional: usize) -> Result<(), ReserveError> {
+        let hasher = self.hasher();
+        let mut entries = self.lock().write();
+        let Entries {
+            indices, values, ..
+        } = &mut *entries;
+        indices
+            .try_reserve(additional, |&index| hasher.hash_one(values[index]))
+            .map_err(|err| match err {
+                hashbrown::TryReserveError::CapacityOverflow => ReserveError::CapacityOverflow,
+                hashbrown::TryReserveError::AllocError { layout } => {
+                    ReserveError::AllocError { layout }
+                }
+            })?;
+        // The error of `Vec` does not tell the cases apart, so tell them apart by the layout.
+        values.try_reserve(additional).map_err(|_| {
+            values
+                .len()
+                .checked_add(additional)
+                .and_then(|capacity| Layout::array::<&T>(capacity).ok())
+                .map_or(ReserveError::CapacityOverflow, |layout| {
+                    ReserveError::AllocError { layout }
+                })
+        })
+    }
+
+    /// Replaces the canonical value for `key` with `new`, which must be equal to `key`.
+    ///
+    /// Interning `key` after the upgrade returns `new`. Existing [`Interned`] handles keep
+    /// pointing at the old allocation, so they no longer compare equal to handles obtained after
+    /// the upgrade.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new` is not equal to `key`, if `key` was not interned before and cannot be
+    /// interned, see [`Interner::try_intern`], or if the interner is
+    /// [frozen](Interner::freeze).
+    pub fn upgrade(&self, key: &T, new: Box<T>) -> Interned<T> {
+        assert!(key == &*new, "upgraded value must be equal to its key");
+        assert!(
+            !self.is_frozen(),
+            "cannot upgrade values of a frozen interner"
+        );
+        let mut entries = self.lock().write();
+        let found = entries
+            .get_hashed(self.hasher().hash_one(key), key)
+            .is_some();
+        self.count(found);
+        if !found {
+            if self.is_sealed() {
+                panic!("{}", InternError::Sealed);
+            }
+            if let Err(err) = entries.check_insert(key) {
+                panic!("{err}");
+            }
+        }
+        let leaked = Box::leak(new);
+        entries.replace(self.hasher(), leaked);
+        self.snapshot.store(None);
+        #[cfg(feature = "thread-local-cache")]
+        self.invalidate_cache();
+        Interned(leaked)
+    }
+}
+
+impl<T: 'static, S: BuildHasher + Default> Interner<[T], S>
+where
+    [T]: Internable,
+{
+    /// Returns an [`Interned<[T]>`] equal to `values`, leaking the buffer of `values` itself
+    /// instead of a copy if it was not interned before. Otherwise `values` is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
+    pub fn intern_vec(&self, values: Vec<T>) -> Interned<[T]> {
+        match self.get_or_lock(&values, None) {
+            Ok(value) => Interned(value),
+            Err(Some(mut entries)) if !self.is_sealed() => {
+                if let Err(err) = entries.check_insert(&values) {
+                    panic!("{err}");
+                }
+                let leaked: &'static [T] = Box::leak(values.into_boxed_slice());
+                entries.insert(leaked);
+                Interned(leaked)
+            }
+            Err(_) => panic!("{}", InternError::Sealed),
+        }
+    }
+}
+
+impl<S: BuildHasher + Default> Interner<str, S> {
+    /// Returns an [`Interned<str>`] equal to `value`, leaking the buffer of `value` itself
+    /// instead of a copy if it was not interned before. Otherwise `value` is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
+    pub fn intern_string(&self, value: String) -> Interned<str> {
+        match self.get_or_lock(&value, None) {
+            Ok(value) => Interned(value),
+            Err(Some(mut entries)) if !self.is_sealed() => {
+                if let Err(err) = entries.check_insert(&value) {
+                    panic!("{err}");
+                }
+                let leaked: &'static str = Box::leak(value.into_boxed_str());
+                entries.insert(leaked);
                 Interned(leaked)
             }
+            Err(_) => panic!("{}", InternError::Sealed),
+        }
+    }
+
+    /// Returns an [`Interned<str>`] equal to the formatted `args`, such as
+    /// `format_args!("node_{id}")`.
+    ///
+    /// The string is formatted once. Strings of up to 256 bytes are formatted on the stack, so
+    /// they only allocate when they were not interned before; longer ones are moved to the heap
+    /// and interned without copying them again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
+    pub fn intern_fmt(&self, args: std::fmt::Arguments<'_>) -> Interned<str> {
+        if let Some(value) = args.as_str() {
+            return self.intern(value);
+        }
+        let mut buf = FmtBuf::new();
+        std::fmt::write(&mut buf, args)
+            .expect("a Display implementation returned an error unexpectedly");
+        if buf.is_spilled() {
+            self.intern_string(buf.spilled)
+        } else {
+            self.intern(buf.as_str())
+        }
+    }
+
+    /// Writes every interned string to `w`, one per line in the order they were first interned,
+    /// without collecting them first.
+    ///
+    /// Backslashes and line breaks inside the strings are escaped as `\\`, `\n` and `\r`.
+    pub fn write_dump(&self, w: &mut impl Write) -> io::Result<()> {
+        let entries = self.lock().read();
+        for value in &entries.values {
+            let mut start = 0;
+            for (i, byte) in value.bytes().enumerate() {
+                let escaped: &[u8] = match byte {
+                    b'\\' => b"\\\\",
+                    b'\n' => b"\\n",
+                    b'\r' => b"\\r",
+                    _ => continue,
+                };
+                w.write_all(&value.as_bytes()[start..i])?;
+                w.write_all(escaped)?;
+                start = i + 1;
+            }
+            w.write_all(&value.as_bytes()[start..])?;
+            w.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: ?Sized> Interned<T> {
+    /// Returns `true` if both handles point at the same address.
+    ///
+    /// This is the identity [`PartialEq`] is based on, except that values may define several
+    /// addresses as equal, such as those of empty strings.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        std::ptr::addr_eq(self.0, other.0)
+    }
+
+    /// Returns a raw pointer to the interned value, which stays valid for the whole program.
+    pub const fn as_ptr(&self) -> *const T {
+        self.0
+    }
+
+    /// Projects the handle to a part of the interned value, such as one of its fields.
+    ///
+    /// The part lives as long as the interned value, so the result compares by address like any
+    /// other handle: projections of the same interned value are equal.
+    pub fn map<U: ?Sized>(self, f: impl FnOnce(&'static T) -> &'static U) -> Interned<U> {
+        Interned(f(self.0))
+    }
+}
+
+impl<T: ?Sized + Internable> Interned<T> {
+    /// Returns the id of the [`Interner`] this value was first interned into, which helps to
+    /// track down values mixed up between interners.
+    ///
+    /// Provenance is only tracked in debug builds, release builds always return `None`. Zero-sized
+    /// values all share the same address, so their provenance is the first interner of them.
+    pub fn provenance(&self) -> Option<InternerId> {
+        provenance::lookup(self.0)
+    }
+}
+
+impl Interned<str> {
+    /// Returns a [`Debug`] wrapper which prints at most `max` bytes of the string, followed by an
+    /// ellipsis and the total length if it is longer.
+    pub fn debug_truncated(&self, max: usize) -> DebugTruncated {
+        DebugTruncated { value: self.0, max }
+    }
+}
+
+/// A [`Debug`] wrapper for long strings, created by [`Interned::debug_truncated`].
+#[derive(Clone, Copy)]
+pub struct DebugTruncated {
+    value: &'static str,
+    max: usize,
+}
+
+impl Debug for DebugTruncated {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.value.len() <= self.max {
+            return Debug::fmt(self.value, f);
         }
+        let mut end = self.max;
+        while !self.value.is_char_boundary(end) {
+            end -= 1;
+        }
+        write!(
+            f,
+            "{:?}... ({} bytes)",
+            &self.value[..end],
+            self.value.len()
+        )
     }
 }
 
@@ -87,6 +1943,30 @@ impl<T: ?Sized> AsRef<T> for Interned<T> {
     }
 }
 
+impl AsRef<Path> for Interned<str> {
+    fn as_ref(&self) -> &Path {
+        self.0.as_ref()
+    }
+}
+
+impl AsRef<Path> for Interned<OsStr> {
+    fn as_ref(&self) -> &Path {
+        self.0.as_ref()
+    }
+}
+
+impl AsRef<OsStr> for Interned<str> {
+    fn as_ref(&self) -> &OsStr {
+        self.0.as_ref()
+    }
+}
+
+impl AsRef<OsStr> for Interned<Path> {
+    fn as_ref(&self) -> &OsStr {
+        self.0.as_ref()
+    }
+}
+
 impl<T: ?Sized> Borrow<T> for Interned<T> {
     fn borrow(&self) -> &T {
         self.0
@@ -115,14 +1995,88 @@ impl<T: ?Sized + Internable> Hash for Interned<T> {
     }
 }
 
+impl<T: ?Sized + Internable + Ord> PartialOrd for Interned<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: ?Sized + Internable + Ord> Ord for Interned<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.0.ref_eq(other.0) {
+            return Ordering::Equal;
+        }
+        // Equal values from different interners are ordered by address to stay consistent with `Eq`.
+        self.0.cmp(other.0).then_with(|| {
+            let this = (self.0 as *const T).cast::<()>();
+            this.cmp(&(other.0 as *const T).cast::<()>())
+        })
+    }
+}
+
 impl<T: ?Sized + Debug> Debug for Interned<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.0.fmt(f)
     }
 }
 
+impl<T: ?Sized + Display> Display for Interned<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: ?Sized> Pointer for Interned<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.as_ptr().cast::<()>(), f)
+    }
+}
+
 impl<T> From<&Interned<T>> for Interned<T> {
     fn from(value: &Interned<T>) -> Self {
         *value
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn frozen_misses_do_not_wait_for_readers() {
+        for perfect in [false, true] {
+            let interner: Interner<str> = Interner::new();
+            let a = interner.intern("a");
+            if perfect {
+                interner.freeze_perfect();
+            } else {
+                interner.freeze();
+            }
+
+            let (send, recv) = mpsc::channel();
+            std::thread::scope(|scope| {
+                // Taking the write lock would wait for this reader.
+                let _reader = interner.lock().read();
+                scope.spawn(|| {
+                    send.send((
+                        interner.try_intern("b"),
+                        interner.try_intern_indexed("b"),
+                        interner.intern_if("b", |_| true),
+                        interner.intern_iter(["a"]),
+                    ))
+                    .unwrap();
+                });
+                let (intern, indexed, intern_if, iter) = recv
+                    .recv_timeout(Duration::from_secs(10))
+                    .expect("a frozen miss waited for the write lock");
+                assert_eq!(intern, Err(InternError::Sealed));
+                assert_eq!(indexed, Err(InternError::Sealed));
+                assert_eq!(intern_if, None);
+                assert_eq!(iter, [a]);
+            });
+        }
+    }
+}
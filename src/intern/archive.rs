@@ -0,0 +1,54 @@
+use std::ops::Deref;
+
+use rkyv::rancor::{Fallible, Source};
+use rkyv::string::{ArchivedString, StringResolver};
+use rkyv::{Archive, Place, Serialize, SerializeUnsized};
+
+use super::{Interned, Interner};
+
+/// An [`Interned<str>`] which can be stored in structs archived with [`rkyv`].
+///
+/// The value is archived as a plain [`ArchivedString`]. After the archive has been mapped,
+/// [`ArchivableInterned::intern_archived`] recovers a live [`Interned<str>`] from it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ArchivableInterned(pub Interned<str>);
+
+impl ArchivableInterned {
+    /// Interns the contents of an archived value, returning a live [`Interned<str>`].
+    pub fn intern_archived(archived: &ArchivedString, interner: &Interner<str>) -> Interned<str> {
+        interner.intern(archived.as_str())
+    }
+}
+
+impl From<Interned<str>> for ArchivableInterned {
+    fn from(value: Interned<str>) -> Self {
+        Self(value)
+    }
+}
+
+impl Deref for ArchivableInterned {
+    type Target = Interned<str>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Archive for ArchivableInterned {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedString::resolve_from_str(&self.0, resolver, out);
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for ArchivableInterned
+where
+    S::Error: Source,
+    str: SerializeUnsized<S>,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str(&self.0, serializer)
+    }
+}
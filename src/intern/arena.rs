@@ -0,0 +1,115 @@
+use std::sync::{Mutex, PoisonError};
+
+/// The size of the chunks allocated by an [`Arena`].
+const CHUNK_SIZE: usize = 16 * 1024;
+
+/// Values larger than this get an allocation of their own instead of wasting the rest of a chunk.
+const MAX_PACKED_LEN: usize = CHUNK_SIZE / 8;
+
+/// A bump allocator which packs leaked values into large chunks, avoiding an allocation per value.
+///
+/// Every [`Interner`](super::Interner) owns one, which is used through
+/// [`Internable::leak_in`](super::Internable::leak_in). Chunks are never freed.
+pub struct Arena {
+    /// The unused remainder of the current chunk.
+    chunk: Mutex<&'static mut [u8]>,
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Arena {
+    pub const fn new() -> Self {
+        Self {
+            chunk: Mutex::new(&mut []),
+        }
+    }
+
+    /// Copies `bytes` into the arena.
+    pub fn alloc_bytes(&self, bytes: &[u8]) -> &'static [u8] {
+        if bytes.len() > MAX_PACKED_LEN {
+            return Box::leak(bytes.into());
+        }
+        let mut chunk = self.chunk.lock().unwrap_or_else(PoisonError::into_inner);
+        if chunk.len() < bytes.len() {
+            *chunk = Box::leak(vec![0; CHUNK_SIZE].into_boxed_slice());
+        }
+        let (slot, rest) = std::mem::take(&mut *chunk).split_at_mut(bytes.len());
+        *chunk = rest;
+        slot.copy_from_slice(bytes);
+        slot
+    }
+
+    /// Copies `value` into the arena.
+    pub fn alloc_str(&self, value: &str) -> &'static str {
+        std::str::from_utf8(self.alloc_bytes(value.as_bytes()))
+            .expect("bytes were copied from a `str`")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_keep_their_contents_across_chunks() {
+        let arena = Arena::new();
+        let values: Vec<String> = (0..5_000).map(|i| format!("value_{i}")).collect();
+        let allocated: Vec<&str> = values.iter().map(|value| arena.alloc_str(value)).collect();
+        assert!(values
+            .iter()
+            .zip(&allocated)
+            .all(|(value, allocated)| value == allocated));
+
+        let mut ranges: Vec<_> = allocated
+            .iter()
+            .map(|value| value.as_bytes().as_ptr_range())
+            .collect();
+        ranges.sort_by_key(|range| range.start);
+        assert!(ranges.windows(2).all(|pair| pair[0].end <= pair[1].start));
+    }
+
+    #[test]
+    fn large_values_get_their_own_allocation() {
+        let arena = Arena::new();
+        let small = arena.alloc_bytes(b"small");
+        let large = vec![7; MAX_PACKED_LEN + 1];
+        let allocated = arena.alloc_bytes(&large);
+        assert_eq!(allocated, large);
+        // The large value did not use up the current chunk.
+        let next = arena.alloc_bytes(b"next");
+        assert_eq!(next.as_ptr(), small.as_ptr_range().end);
+        assert_eq!(arena.alloc_bytes(&[]), []);
+    }
+
+    #[test]
+    fn concurrent_allocations_do_not_overlap() {
+        let arena = Arena::new();
+        let allocated: Vec<Vec<&[u8]>> = std::thread::scope(|scope| {
+            let threads: Vec<_> = (0..8u8)
+                .map(|thread| {
+                    let arena = &arena;
+                    scope.spawn(move || {
+                        (0..1_000)
+                            .map(|len| arena.alloc_bytes(&vec![thread; len % 64 + 1]))
+                            .collect()
+                    })
+                })
+                .collect();
+            threads
+                .into_iter()
+                .map(|thread| thread.join().unwrap())
+                .collect()
+        });
+
+        for (thread, values) in allocated.iter().enumerate() {
+            for (len, value) in values.iter().enumerate() {
+                assert_eq!(value.len(), len % 64 + 1);
+                assert!(value.iter().all(|&byte| usize::from(byte) == thread));
+            }
+        }
+    }
+}
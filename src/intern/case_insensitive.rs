@@ -0,0 +1,84 @@
+use std::hash::BuildHasher;
+
+use super::{DefaultHashBuilder, InternError, Interned, Interner};
+
+/// ASCII strings up to this length are lowercased on the stack.
+const STACK_LEN: usize = 128;
+
+/// A string interner which ignores case, interning the lowercase form of every string.
+///
+/// Strings which differ only in case intern to the same [`Interned<str>`], which holds their
+/// lowercase form. Lowercase ASCII strings are looked up as they are, and other ASCII strings of
+/// up to 128 bytes are lowercased without allocating. Other strings are lowercased with
+/// [`str::to_lowercase`].
+pub struct CaseInsensitiveInterner<S = DefaultHashBuilder> {
+    inner: Interner<str, S>,
+}
+
+impl<S> Default for CaseInsensitiveInterner<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> CaseInsensitiveInterner<S> {
+    pub const fn new() -> Self {
+        Self {
+            inner: Interner::new(),
+        }
+    }
+}
+
+impl<S: BuildHasher + Default> CaseInsensitiveInterner<S> {
+    /// Returns the [`Interned<str>`] of the lowercase form of `value`, leaking a copy of it if it
+    /// was not interned before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
+    pub fn intern(&self, value: &str) -> Interned<str> {
+        with_lowercase(value, |value| self.inner.intern(value))
+    }
+
+    /// Like [`CaseInsensitiveInterner::intern`], but returns an error instead of panicking if
+    /// `value` cannot be interned.
+    pub fn try_intern(&self, value: &str) -> Result<Interned<str>, InternError> {
+        with_lowercase(value, |value| self.inner.try_intern(value))
+    }
+
+    /// Returns the [`Interned<str>`] of the lowercase form of `value` if it was interned before,
+    /// without interning it otherwise.
+    pub fn get(&self, value: &str) -> Option<Interned<str>> {
+        with_lowercase(value, |value| self.inner.get(value))
+    }
+
+    /// Returns the number of distinct lowercase strings interned.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if no string has been interned.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+/// Calls `f` with the lowercase form of `value`, avoiding an allocation where possible.
+fn with_lowercase<R>(value: &str, f: impl FnOnce(&str) -> R) -> R {
+    if !value.is_ascii() {
+        return f(&value.to_lowercase());
+    }
+    if !value.bytes().any(|byte| byte.is_ascii_uppercase()) {
+        return f(value);
+    }
+    if value.len() > STACK_LEN {
+        return f(&value.to_ascii_lowercase());
+    }
+    let mut buf = [0; STACK_LEN];
+    let buf = &mut buf[..value.len()];
+    buf.copy_from_slice(value.as_bytes());
+    buf.make_ascii_lowercase();
+    f(std::str::from_utf8(buf).expect("lowercasing ASCII keeps it ASCII"))
+}
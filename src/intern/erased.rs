@@ -0,0 +1,114 @@
+use std::any::TypeId;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::mem::{self, MaybeUninit};
+
+use super::{Internable, Interned};
+
+/// The storage of an [`Interned<T>`], which is a thin or a fat pointer.
+type Repr = MaybeUninit<[usize; 2]>;
+
+/// An [`Interned<T>`] with its type erased, so values of different types can be stored in one
+/// container and [downcast](ErasedInterned::downcast) back later.
+///
+/// Two erased handles are equal if they erase the same type and the [`Interned<T>`]s are equal,
+/// which compares them with [`Internable::ref_eq`] and hashes them with [`Internable::ref_hash`].
+#[derive(Clone, Copy)]
+pub struct ErasedInterned {
+    repr: Repr,
+    ptr: *const (),
+    type_id: TypeId,
+    eq: fn(&Repr, &Repr) -> bool,
+    hash: fn(&Repr, &mut dyn Hasher),
+}
+
+// SAFETY: `repr` and `ptr` point at the value of an `Interned<T>` where `T: Sync`, which is
+// `Send` and `Sync`.
+unsafe impl Send for ErasedInterned {}
+// SAFETY: see above.
+unsafe impl Sync for ErasedInterned {}
+
+impl ErasedInterned {
+    /// Erases the type of `value`.
+    pub fn new<T: ?Sized + Internable + Sync + 'static>(value: Interned<T>) -> Self {
+        const { assert!(mem::size_of::<Interned<T>>() <= mem::size_of::<Repr>()) };
+        let mut repr = Repr::uninit();
+        // SAFETY: `repr` is large enough for `value` and has at least its alignment.
+        unsafe { repr.as_mut_ptr().cast::<Interned<T>>().write(value) };
+        Self {
+            repr,
+            ptr: value.as_ptr().cast(),
+            type_id: TypeId::of::<T>(),
+            eq: eq_repr::<T>,
+            hash: hash_repr::<T>,
+        }
+    }
+
+    /// Returns `true` if the erased value is an [`Interned<T>`].
+    pub fn is<T: ?Sized + 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+
+    /// Returns the erased [`Interned<T>`], or `None` if it has another type.
+    pub fn downcast<T: ?Sized + 'static>(&self) -> Option<Interned<T>> {
+        // SAFETY: `repr` holds an `Interned<T>` if the type ids match.
+        self.is::<T>().then(|| unsafe { read(&self.repr) })
+    }
+
+    /// Returns the address of the erased value.
+    pub fn as_ptr(&self) -> *const () {
+        self.ptr
+    }
+}
+
+impl<T: ?Sized + Internable + Sync + 'static> From<Interned<T>> for ErasedInterned {
+    fn from(value: Interned<T>) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Reads the [`Interned<T>`] stored in `repr`.
+///
+/// # Safety
+///
+/// `repr` must have been written by [`ErasedInterned::new::<T>`].
+unsafe fn read<T: ?Sized + 'static>(repr: &Repr) -> Interned<T> {
+    // SAFETY: guaranteed by the caller.
+    unsafe { repr.as_ptr().cast::<Interned<T>>().read() }
+}
+
+/// Compares two erased [`Interned<T>`]s, which is only called once their type ids match.
+fn eq_repr<T: ?Sized + Internable + 'static>(a: &Repr, b: &Repr) -> bool {
+    // SAFETY: both were erased from an `Interned<T>`, since this function is stored alongside
+    // `TypeId::of::<T>()` and only called if the other handle has the same type id.
+    unsafe { read::<T>(a) == read::<T>(b) }
+}
+
+fn hash_repr<T: ?Sized + Internable + 'static>(repr: &Repr, mut state: &mut dyn Hasher) {
+    // SAFETY: this function is only stored alongside an erased `Interned<T>`.
+    unsafe { read::<T>(repr) }.hash(&mut state);
+}
+
+impl PartialEq for ErasedInterned {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_id == other.type_id && (self.eq)(&self.repr, &other.repr)
+    }
+}
+
+impl Eq for ErasedInterned {}
+
+impl Hash for ErasedInterned {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.type_id.hash(state);
+        (self.hash)(&self.repr, state);
+    }
+}
+
+impl Debug for ErasedInterned {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ErasedInterned")
+            .field("ptr", &self.ptr)
+            .field("type_id", &self.type_id)
+            .finish()
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use std::hash::BuildHasher;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+
+use hashbrown::HashTable;
+
+use super::{DefaultHashBuilder, Internable, ToBoxed};
+
+/// An interner which holds at most a fixed number of values, evicting the least recently interned
+/// one to make room for a new value.
+///
+/// Values are reference counted, so evicted values stay alive as long as they are used. Interning
+/// an evicted value again creates a new copy, which is not identical to the old one, so this suits
+/// caches of normalized values rather than canonical identities.
+pub struct EvictingInterner<T: ?Sized + 'static, S = DefaultHashBuilder> {
+    capacity: usize,
+    entries: OnceLock<Mutex<EvictingEntries<T, S>>>,
+}
+
+struct EvictingEntries<T: ?Sized, S> {
+    hasher: S,
+    /// The values with the tick they were last interned at.
+    values: HashTable<(Arc<T>, u64)>,
+    /// The hashes of the values by the tick they were last interned at.
+    recency: BTreeMap<u64, u64>,
+    tick: u64,
+}
+
+impl<T: ?Sized, S> EvictingInterner<T, S> {
+    /// Creates an interner holding at most `capacity` values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub const fn new(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "an evicting interner must hold at least one value"
+        );
+        Self {
+            capacity,
+            entries: OnceLock::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, EvictingEntries<T, S>>
+    where
+        S: Default,
+    {
+        self.entries
+            .get_or_init(|| {
+                Mutex::new(EvictingEntries {
+                    hasher: S::default(),
+                    values: HashTable::new(),
+                    recency: BTreeMap::new(),
+                    tick: 0,
+                })
+            })
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the number of values held.
+    pub fn len(&self) -> usize
+    where
+        S: Default,
+    {
+        self.lock().values.len()
+    }
+
+    /// Returns `true` if the interner holds no values.
+    pub fn is_empty(&self) -> bool
+    where
+        S: Default,
+    {
+        self.len() == 0
+    }
+}
+
+impl<T: Internable + ToBoxed + ?Sized, S: BuildHasher + Default> EvictingInterner<T, S> {
+    /// Returns the value equal to `value`, allocating a copy of `value` if the interner holds no
+    /// equal value, and marks it as the most recently interned one.
+    #[must_use]
+    pub fn intern(&self, value: &T) -> Arc<T> {
+        let mut entries = self.lock();
+        let EvictingEntries {
+            hasher,
+            values,
+            recency,
+            tick,
+        } = &mut *entries;
+        *tick += 1;
+        let hash = hasher.hash_one(value);
+        if let Some((existing, last)) = values.find_mut(hash, |(existing, _)| **existing == *value)
+        {
+            recency.remove(last);
+            recency.insert(*tick, hash);
+            *last = *tick;
+            return Arc::clone(existing);
+        }
+
+        if values.len() >= self.capacity {
+            let (oldest, oldest_hash) = recency.pop_first().expect("a full interner has values");
+            if let Ok(entry) = values.find_entry(oldest_hash, |&(_, last)| last == oldest) {
+                entry.remove();
+            }
+        }
+        let value: Arc<T> = value.to_boxed().into();
+        values.insert_unique(hash, (Arc::clone(&value), *tick), |(value, _)| {
+            hasher.hash_one(&**value)
+        });
+        recency.insert(*tick, hash);
+        value
+    }
+}
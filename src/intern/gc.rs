@@ -0,0 +1,136 @@
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+
+use hashbrown::HashTable;
+
+use super::{DefaultHashBuilder, Internable, ToBoxed};
+
+/// An interner whose unused values are freed by an explicit [`sweep`](GcInterner::sweep).
+///
+/// Values are held by reference counted [`GcInterned`] handles. Unlike with
+/// [`RcInterner`](super::RcInterner), dropping a handle never takes a lock. Values without live
+/// handles are only removed when the interner is swept.
+pub struct GcInterner<T: ?Sized + 'static, S = DefaultHashBuilder> {
+    entries: OnceLock<Mutex<GcEntries<T, S>>>,
+}
+
+struct GcEntries<T: ?Sized, S> {
+    hasher: S,
+    values: HashTable<Arc<T>>,
+}
+
+impl<T: ?Sized, S> Default for GcInterner<T, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized, S> GcInterner<T, S> {
+    pub const fn new() -> Self {
+        Self {
+            entries: OnceLock::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, GcEntries<T, S>>
+    where
+        S: Default,
+    {
+        self.entries
+            .get_or_init(|| {
+                Mutex::new(GcEntries {
+                    hasher: S::default(),
+                    values: HashTable::new(),
+                })
+            })
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Removes and frees every value without live handles, returning how many were removed.
+    pub fn sweep(&self) -> usize
+    where
+        S: Default,
+    {
+        let mut entries = self.lock();
+        let len = entries.values.len();
+        // Handles can only be created under the lock or from other handles, so a value without
+        // handles cannot gain one while it is being removed.
+        entries.values.retain(|value| Arc::strong_count(value) > 1);
+        len - entries.values.len()
+    }
+
+    /// Returns the number of values held, including those not yet [swept](GcInterner::sweep).
+    pub fn len(&self) -> usize
+    where
+        S: Default,
+    {
+        self.lock().values.len()
+    }
+
+    /// Returns `true` if the interner holds no values.
+    pub fn is_empty(&self) -> bool
+    where
+        S: Default,
+    {
+        self.len() == 0
+    }
+}
+
+impl<T: Internable + ToBoxed + ?Sized, S: BuildHasher + Default> GcInterner<T, S> {
+    /// Returns a [`GcInterned`] equal to `value`, allocating a copy of `value` if the interner
+    /// holds no equal value.
+    #[must_use]
+    pub fn intern(&self, value: &T) -> GcInterned<T> {
+        let mut entries = self.lock();
+        let GcEntries { hasher, values } = &mut *entries;
+        let hash = hasher.hash_one(value);
+        if let Some(existing) = values.find(hash, |existing| **existing == *value) {
+            return GcInterned(Arc::clone(existing));
+        }
+        let value: Arc<T> = value.to_boxed().into();
+        values.insert_unique(hash, Arc::clone(&value), |value| hasher.hash_one(&**value));
+        GcInterned(value)
+    }
+}
+
+/// A reference counted value interned by a [`GcInterner`].
+///
+/// Like [`Interned<T>`](super::Interned), equality and hashing use the address of the value.
+pub struct GcInterned<T: ?Sized>(pub(super) Arc<T>);
+
+impl<T: ?Sized> Deref for GcInterned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> Clone for GcInterned<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T: ?Sized + Internable> PartialEq for GcInterned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.ref_eq(&other.0)
+    }
+}
+
+impl<T: ?Sized + Internable> Eq for GcInterned<T> {}
+
+impl<T: ?Sized + Internable> Hash for GcInterned<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.ref_hash(state);
+    }
+}
+
+impl<T: ?Sized + Debug> Debug for GcInterned<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
@@ -0,0 +1,24 @@
+use kstring::{KString, KStringCow, KStringRef};
+
+use super::Interned;
+
+// Interned strings live for the rest of the program, so they can be referenced without copying.
+// Interning a `KString` needs no conversion, as it dereferences to `str`.
+
+impl From<Interned<str>> for KString {
+    fn from(value: Interned<str>) -> Self {
+        KString::from_static(value.0)
+    }
+}
+
+impl From<Interned<str>> for KStringRef<'static> {
+    fn from(value: Interned<str>) -> Self {
+        KStringRef::from_static(value.0)
+    }
+}
+
+impl From<Interned<str>> for KStringCow<'static> {
+    fn from(value: Interned<str>) -> Self {
+        KStringCow::from_static(value.0)
+    }
+}
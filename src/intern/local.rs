@@ -0,0 +1,116 @@
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash, Hasher, RandomState};
+use std::ops::Deref;
+
+use hashbrown::HashTable;
+
+use super::{Internable, ToBoxed};
+
+/// A single-threaded interner which owns its values instead of leaking them.
+///
+/// Values are freed when the interner is dropped, so the [`LocalInterned`] handles it creates
+/// borrow from it. This suits per-job data that should not outlive the job.
+pub struct LocalInterner<T: ?Sized> {
+    hasher: RandomState,
+    entries: RefCell<LocalEntries<T>>,
+}
+
+struct LocalEntries<T: ?Sized> {
+    /// Indices into `values`, hashed by the value they point at.
+    indices: HashTable<usize>,
+    values: Vec<Box<T>>,
+}
+
+impl<T: ?Sized> Default for LocalInterner<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized> LocalInterner<T> {
+    pub fn new() -> Self {
+        Self {
+            hasher: RandomState::new(),
+            entries: RefCell::new(LocalEntries {
+                indices: HashTable::new(),
+                values: Vec::new(),
+            }),
+        }
+    }
+
+    /// Returns the number of distinct values interned.
+    pub fn len(&self) -> usize {
+        self.entries.borrow().values.len()
+    }
+
+    /// Returns `true` if no value has been interned.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Internable + ToBoxed + ?Sized> LocalInterner<T> {
+    /// Returns a [`LocalInterned`] equal to `value`, storing a copy of `value` in the interner if
+    /// it was not interned before.
+    #[must_use]
+    pub fn intern(&self, value: &T) -> LocalInterned<'_, T> {
+        let hash = self.hasher.hash_one(value);
+        let mut entries = self.entries.borrow_mut();
+        let LocalEntries { indices, values } = &mut *entries;
+        let index = match indices.find(hash, |&index| *values[index] == *value) {
+            Some(&index) => index,
+            None => {
+                let index = values.len();
+                values.push(value.to_boxed());
+                indices.insert_unique(hash, index, |&index| self.hasher.hash_one(&*values[index]));
+                index
+            }
+        };
+        let value: *const T = &*values[index];
+        // SAFETY: The values are boxed, so they do not move when `values` grows, and they are only
+        // dropped together with the interner, which is borrowed for the lifetime of the handle.
+        LocalInterned(unsafe { &*value })
+    }
+}
+
+/// A value interned by a [`LocalInterner`], which lives as long as the interner.
+///
+/// Like [`Interned<T>`](super::Interned), equality and hashing use the address of the value.
+pub struct LocalInterned<'a, T: ?Sized>(pub &'a T);
+
+impl<T: ?Sized> Deref for LocalInterned<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl<T: ?Sized> Clone for LocalInterned<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for LocalInterned<'_, T> {}
+
+impl<T: ?Sized + Internable> PartialEq for LocalInterned<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.ref_eq(other.0)
+    }
+}
+
+impl<T: ?Sized + Internable> Eq for LocalInterned<'_, T> {}
+
+impl<T: ?Sized + Internable> Hash for LocalInterned<'_, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.ref_hash(state);
+    }
+}
+
+impl<T: ?Sized + Debug> Debug for LocalInterned<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
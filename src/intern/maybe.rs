@@ -0,0 +1,98 @@
+use std::fmt::{Debug, Display};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::ops::Deref;
+
+use super::{Internable, Interned, Interner};
+
+/// A value which is either borrowed or already interned, so it is only interned when needed.
+pub enum MaybeInterned<'a, T: ?Sized + 'static> {
+    /// A borrowed value, which may not be interned yet.
+    Borrowed(&'a T),
+    /// An interned value.
+    Interned(Interned<T>),
+}
+
+impl<T: Internable + ?Sized> MaybeInterned<'_, T> {
+    /// Returns the value interned into `interner`, interning a borrowed value if it was not
+    /// interned before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
+    pub fn intern<S: BuildHasher + Default>(&self, interner: &Interner<T, S>) -> Interned<T> {
+        match self {
+            Self::Borrowed(value) => interner.intern(value),
+            Self::Interned(value) => interner.intern_ref(value.0),
+        }
+    }
+}
+
+impl<T: ?Sized> MaybeInterned<'_, T> {
+    /// Returns `true` if the value is already interned.
+    pub fn is_interned(&self) -> bool {
+        matches!(self, Self::Interned(_))
+    }
+}
+
+impl<T: ?Sized> Deref for MaybeInterned<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Borrowed(value) => value,
+            Self::Interned(value) => value.0,
+        }
+    }
+}
+
+impl<T: ?Sized> Clone for MaybeInterned<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for MaybeInterned<'_, T> {}
+
+impl<'a, T: ?Sized> From<&'a T> for MaybeInterned<'a, T> {
+    fn from(value: &'a T) -> Self {
+        Self::Borrowed(value)
+    }
+}
+
+impl<T: ?Sized> From<Interned<T>> for MaybeInterned<'_, T> {
+    fn from(value: Interned<T>) -> Self {
+        Self::Interned(value)
+    }
+}
+
+/// Compares the values, taking a shortcut if both are interned at the same address.
+impl<T: Internable + ?Sized> PartialEq for MaybeInterned<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Interned(a), Self::Interned(b)) if a == b => true,
+            _ => **self == **other,
+        }
+    }
+}
+
+impl<T: Internable + ?Sized> Eq for MaybeInterned<'_, T> {}
+
+impl<T: Internable + ?Sized> Hash for MaybeInterned<'_, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
+    }
+}
+
+impl<T: ?Sized + Debug> Debug for MaybeInterned<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: ?Sized + Display> Display for MaybeInterned<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
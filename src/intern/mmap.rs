@@ -0,0 +1,124 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use super::Interned;
+
+/// The size of the count and offset fields in a serialized table.
+const WORD: usize = std::mem::size_of::<u64>();
+
+/// A read-only interner resolving strings from a serialized, memory mapped table.
+///
+/// Tables are written with [`MmapStringInterner::write_table`] and consist of a little endian
+/// `u64` count, `count + 1` little endian `u64` offsets into the string data, and the sorted
+/// string data itself. Lookups binary search the table and return [`Interned<str>`] values
+/// pointing into the mapped region, so no strings are allocated.
+pub struct MmapStringInterner {
+    offsets: &'static [u8],
+    data: &'static [u8],
+}
+
+impl MmapStringInterner {
+    /// Writes a table containing `strings` to `w`.
+    pub fn write_table<'a>(
+        strings: impl IntoIterator<Item = &'a str>,
+        w: &mut impl Write,
+    ) -> io::Result<()> {
+        let mut strings: Vec<&str> = strings.into_iter().collect();
+        strings.sort_unstable();
+        strings.dedup();
+        w.write_all(&(strings.len() as u64).to_le_bytes())?;
+        let mut offset = 0u64;
+        w.write_all(&offset.to_le_bytes())?;
+        for string in &strings {
+            offset += string.len() as u64;
+            w.write_all(&offset.to_le_bytes())?;
+        }
+        for string in &strings {
+            w.write_all(string.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Maps the table stored at `path`. The mapping is never unmapped, so that the strings can be
+    /// handed out as [`Interned<str>`].
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the program is running, see
+    /// [`Mmap::map`].
+    pub unsafe fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: forwarded to the caller.
+        let map = unsafe { Mmap::map(&file)? };
+        Self::from_bytes(Box::leak(Box::new(map)))
+    }
+
+    /// Reads a table from `bytes`, for example one embedded with [`include_bytes!`].
+    pub fn from_bytes(bytes: &'static [u8]) -> io::Result<Self> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let count = read_word(bytes, 0).ok_or_else(|| invalid("missing string count"))?;
+        let offsets_len = usize::try_from(count)
+            .ok()
+            .and_then(|count| count.checked_add(1)?.checked_mul(WORD))
+            .ok_or_else(|| invalid("string count is too large"))?;
+        let offsets = bytes
+            .get(WORD..)
+            .and_then(|rest| rest.get(..offsets_len))
+            .ok_or_else(|| invalid("missing string offsets"))?;
+        let data = &bytes[WORD + offsets_len..];
+        let table = Self { offsets, data };
+
+        let mut previous: Option<&str> = None;
+        for index in 0..table.len() {
+            let bytes = table
+                .entry(index)
+                .ok_or_else(|| invalid("string offsets are out of bounds"))?;
+            let string =
+                std::str::from_utf8(bytes).map_err(|_| invalid("string is not valid UTF-8"))?;
+            if previous.is_some_and(|previous| previous >= string) {
+                return Err(invalid("strings are not sorted"));
+            }
+            previous = Some(string);
+        }
+        Ok(table)
+    }
+
+    /// Returns the number of strings in the table.
+    pub fn len(&self) -> usize {
+        self.offsets.len() / WORD - 1
+    }
+
+    /// Returns `true` if the table contains no strings.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the [`Interned<str>`] in the table equal to `value`, if any.
+    pub fn get(&self, value: &str) -> Option<Interned<str>> {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let entry = self.entry(mid)?;
+            match entry.cmp(value.as_bytes()) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return std::str::from_utf8(entry).ok().map(Interned),
+            }
+        }
+        None
+    }
+
+    fn entry(&self, index: usize) -> Option<&'static [u8]> {
+        let start = usize::try_from(read_word(self.offsets, index * WORD)?).ok()?;
+        let end = usize::try_from(read_word(self.offsets, (index + 1) * WORD)?).ok()?;
+        self.data.get(start..end)
+    }
+}
+
+fn read_word(bytes: &[u8], at: usize) -> Option<u64> {
+    let word = bytes.get(at..at.checked_add(WORD)?)?;
+    Some(u64::from_le_bytes(word.try_into().ok()?))
+}
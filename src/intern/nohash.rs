@@ -0,0 +1,60 @@
+use std::hash::{Hash, Hasher};
+
+use nohash_hasher::IsEnabled;
+
+use super::{mix_address, Internable, Interned};
+
+/// An [`Interned<T>`] which hashes as the mixed address of its value and the [`TypeId`] of the
+/// value, so it can be used as a key with [`nohash_hasher::BuildNoHashHasher`].
+///
+/// Equality is the same as for [`Interned<T>`]. Empty values may compare equal at different
+/// addresses, so zero-sized values hash by their type alone. That keeps unit-struct labels behind
+/// `Interned<dyn Label>`, which all share one address, in different buckets.
+///
+/// [`TypeId`]: std::any::TypeId
+pub struct NoHashInterned<T: ?Sized + 'static>(pub Interned<T>);
+
+impl<T: ?Sized + Internable> Hash for NoHashInterned<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let value = self.0 .0;
+        let mut type_hash = crate::StableHasher::new();
+        value.value_type_id().hash(&mut type_hash);
+        // The hasher only accepts a single write, so the hash cannot include the length.
+        let address = if std::mem::size_of_val(value) == 0 {
+            0
+        } else {
+            mix_address((value as *const T).cast())
+        };
+        state.write_u64(type_hash.finish() ^ address);
+    }
+}
+
+impl<T: ?Sized + Internable> IsEnabled for NoHashInterned<T> {}
+
+impl<T: ?Sized + Internable> PartialEq for NoHashInterned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: ?Sized + Internable> Eq for NoHashInterned<T> {}
+
+impl<T: ?Sized> Clone for NoHashInterned<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for NoHashInterned<T> {}
+
+impl<T: ?Sized + std::fmt::Debug> std::fmt::Debug for NoHashInterned<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: ?Sized> From<Interned<T>> for NoHashInterned<T> {
+    fn from(value: Interned<T>) -> Self {
+        Self(value)
+    }
+}
@@ -0,0 +1,49 @@
+use std::hash::BuildHasher;
+
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+
+use super::{Internable, Interned, Interner, ShardedInterner};
+
+impl<T: Internable + Sync + ?Sized, S: BuildHasher + Default + Send + Sync> Interner<T, S> {
+    /// Interns every value of `values` on the rayon thread pool, returning the [`Interned<T>`]s in
+    /// the same order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the values was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
+    pub fn par_intern_iter<'v, I>(&self, values: I) -> Vec<Interned<T>>
+    where
+        I: IntoParallelIterator<Item = &'v T>,
+        I::Iter: IndexedParallelIterator,
+        T: 'v,
+    {
+        values
+            .into_par_iter()
+            .map(|value| self.intern(value))
+            .collect()
+    }
+}
+
+impl<T: Internable + Sync + ?Sized> ShardedInterner<T> {
+    /// Interns every value of `values` on the rayon thread pool, returning the [`Interned<T>`]s in
+    /// the same order. Threads interning into different shards do not contend.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the values was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
+    pub fn par_intern_iter<'v, I>(&self, values: I) -> Vec<Interned<T>>
+    where
+        I: IntoParallelIterator<Item = &'v T>,
+        I::Iter: IndexedParallelIterator,
+        T: 'v,
+    {
+        values
+            .into_par_iter()
+            .map(|value| self.intern(value))
+            .collect()
+    }
+}
@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use super::sync::RwLock;
+use super::{Internable, Interned};
+
+/// The parents of the labels of one label trait, registered when each label is first interned.
+///
+/// This backs the `parent` method generated by `define_label!` with the `hierarchy` flag.
+#[doc(hidden)]
+pub struct LabelParents<T: ?Sized + 'static>(OnceLock<RwLock<HashMap<Interned<T>, Interned<T>>>>);
+
+impl<T: ?Sized> Default for LabelParents<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized> LabelParents<T> {
+    pub const fn new() -> Self {
+        Self(OnceLock::new())
+    }
+}
+
+impl<T: Internable + ?Sized> LabelParents<T> {
+    /// Registers `parent` as the parent of `label`, unless `label` already has one.
+    pub fn register(&self, label: Interned<T>, parent: Interned<T>) {
+        if self.parents().read().contains_key(&label) {
+            return;
+        }
+        self.parents().write().entry(label).or_insert(parent);
+    }
+
+    /// Returns the parent registered for `label`, if any.
+    pub fn get(&self, label: Interned<T>) -> Option<Interned<T>> {
+        self.parents().read().get(&label).copied()
+    }
+
+    fn parents(&self) -> &RwLock<HashMap<Interned<T>, Interned<T>>> {
+        self.0.get_or_init(|| RwLock::new(HashMap::new()))
+    }
+}
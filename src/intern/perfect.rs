@@ -0,0 +1,169 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// The average number of values per displacement bucket.
+const LAMBDA: usize = 4;
+
+/// The number of seeds tried before giving up on a table.
+const MAX_SEEDS: u64 = 16;
+
+/// The number of first displacements tried per bucket, each with every second displacement.
+const MAX_D1: u32 = 32;
+
+/// A minimal perfect hash table over a fixed set of values, built with the hash and displace
+/// algorithm.
+///
+/// Every value is found with a single hash and one array index, without probing.
+pub(super) struct PerfectTable<T: ?Sized + 'static> {
+    seed: u64,
+    displacements: Vec<(u32, u32)>,
+    values: Vec<&'static T>,
+}
+
+/// The bucket and the two displacement hashes of a value.
+struct Hashes {
+    bucket: u32,
+    f1: u32,
+    f2: u32,
+}
+
+impl<T: Hash + Eq + ?Sized> PerfectTable<T> {
+    /// Builds a table over `values`, which must not contain equal values.
+    ///
+    /// Returns `None` if no seed separates the values, such as when two of them feed the same
+    /// data to the hasher.
+    pub(super) fn new(values: &[&'static T]) -> Option<Self> {
+        (0..MAX_SEEDS).find_map(|seed| Self::try_new(seed, values))
+    }
+
+    fn try_new(seed: u64, values: &[&'static T]) -> Option<Self> {
+        let len = values.len();
+        let buckets_len = len.div_ceil(LAMBDA).max(1);
+        let hashes: Vec<_> = values.iter().map(|value| hashes(seed, *value)).collect();
+
+        let mut buckets = vec![Vec::new(); buckets_len];
+        for (i, hashes) in hashes.iter().enumerate() {
+            buckets[hashes.bucket as usize % buckets_len].push(i);
+        }
+        let mut order: Vec<_> = (0..buckets_len).collect();
+        order.sort_by_key(|&bucket| std::cmp::Reverse(buckets[bucket].len()));
+
+        let mut displacements = vec![(0, 0); buckets_len];
+        let mut slots: Vec<Option<usize>> = vec![None; len];
+        let mut placed = Vec::with_capacity(LAMBDA);
+        'buckets: for bucket in order {
+            let keys = &buckets[bucket];
+            if keys.is_empty() {
+                break;
+            }
+            // Values with the same hashes land in the same slot whatever the displacement.
+            for (i, &key) in keys.iter().enumerate() {
+                let (f1, f2) = (hashes[key].f1, hashes[key].f2);
+                if keys[..i]
+                    .iter()
+                    .any(|&other| (hashes[other].f1, hashes[other].f2) == (f1, f2))
+                {
+                    return None;
+                }
+            }
+            for d1 in 0..MAX_D1.min(len as u32) {
+                'displacements: for d2 in 0..len as u32 {
+                    placed.clear();
+                    for &key in keys {
+                        let slot = displace(&hashes[key], d1, d2) as usize % len;
+                        if slots[slot].is_some() || placed.contains(&slot) {
+                            continue 'displacements;
+                        }
+                        placed.push(slot);
+                    }
+                    for (&key, &slot) in keys.iter().zip(&placed) {
+                        slots[slot] = Some(key);
+                    }
+                    displacements[bucket] = (d1, d2);
+                    continue 'buckets;
+                }
+            }
+            return None;
+        }
+
+        Some(Self {
+            seed,
+            displacements,
+            values: slots
+                .into_iter()
+                .map(|key| values[key.expect("every slot is filled")])
+                .collect(),
+        })
+    }
+
+    /// Returns the value in the table equal to `value`, if any.
+    pub(super) fn get(&self, value: &T) -> Option<&'static T> {
+        if self.values.is_empty() {
+            return None;
+        }
+        let hashes = hashes(self.seed, value);
+        let (d1, d2) = self.displacements[hashes.bucket as usize % self.displacements.len()];
+        let candidate = self.values[displace(&hashes, d1, d2) as usize % self.values.len()];
+        (candidate == value).then_some(candidate)
+    }
+}
+
+fn hashes<T: Hash + ?Sized>(seed: u64, value: &T) -> Hashes {
+    let mut state = DefaultHasher::new();
+    state.write_u64(seed);
+    value.hash(&mut state);
+    let low = state.finish();
+    state.write_u8(0);
+    let high = state.finish();
+    Hashes {
+        bucket: (high >> 32) as u32,
+        f1: low as u32,
+        f2: (low >> 32) as u32,
+    }
+}
+
+fn displace(hashes: &Hashes, d1: u32, d2: u32) -> u32 {
+    d2.wrapping_add(hashes.f1.wrapping_mul(d1))
+        .wrapping_add(hashes.f2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leak(values: impl IntoIterator<Item = String>) -> Vec<&'static str> {
+        values
+            .into_iter()
+            .map(|value| &*Box::leak(value.into_boxed_str()))
+            .collect()
+    }
+
+    #[test]
+    fn every_value_is_found_in_its_slot() {
+        for len in [0, 1, 2, 3, 5, 100, 10_000] {
+            let values = leak((0..len).map(|i| format!("value_{i}")));
+            let table = PerfectTable::new(&values).unwrap();
+            assert_eq!(table.values.len(), len);
+            for value in &values {
+                assert!(std::ptr::eq(table.get(value).unwrap(), *value));
+            }
+            assert_eq!(table.get("value_-1"), None);
+            assert_eq!(table.get(&format!("value_{len}")), None);
+        }
+    }
+
+    #[derive(PartialEq, Eq)]
+    struct Opaque(u32);
+
+    impl Hash for Opaque {
+        fn hash<H: Hasher>(&self, _: &mut H) {}
+    }
+
+    #[test]
+    fn values_hashing_alike_have_no_table() {
+        let values: Vec<&'static Opaque> =
+            (0..3).map(|i| &*Box::leak(Box::new(Opaque(i)))).collect();
+        assert!(PerfectTable::new(&values).is_none());
+        assert!(PerfectTable::new(&values[..1]).is_some());
+    }
+}
@@ -0,0 +1,70 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::num::NonZeroU64;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex, MutexGuard, PoisonError};
+
+use super::mix_address;
+
+/// Identifies an [`Interner`](super::Interner).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InternerId(NonZeroU64);
+
+impl InternerId {
+    pub(super) fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        let id = NEXT.fetch_add(1, Ordering::Relaxed);
+        Self(NonZeroU64::new(id).expect("ran out of interner ids"))
+    }
+}
+
+/// The number of shards of [`PROVENANCE`], so interners on different threads rarely contend.
+const SHARDS: usize = 16;
+
+/// Identifies an interned value by its address, size and type, since values of different types or
+/// sizes may start at the same address.
+type Key = (usize, usize, TypeId);
+
+/// Maps interned values to the interner that first interned them, sharded by address.
+static PROVENANCE: LazyLock<[Mutex<HashMap<Key, InternerId>>; SHARDS]> =
+    LazyLock::new(|| std::array::from_fn(|_| Mutex::default()));
+
+/// Returns the key of `value` and the shard holding it.
+///
+/// Zero-sized values all share the same dangling address, so their provenance is the first
+/// interner which interned a zero-sized value of their type.
+fn shard<T: ?Sized + 'static>(value: &T) -> (Key, MutexGuard<'static, HashMap<Key, InternerId>>) {
+    let ptr = (value as *const T).cast::<()>();
+    let key = (ptr.addr(), std::mem::size_of_val(value), TypeId::of::<T>());
+    let shard = &PROVENANCE[mix_address(ptr) as usize % SHARDS];
+    (key, shard.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// Records that `value` was interned into the interner `id`, in debug builds only.
+pub(super) fn record<T: ?Sized + 'static>(value: &T, id: InternerId) {
+    if cfg!(debug_assertions) {
+        let (key, mut shard) = shard(value);
+        shard.entry(key).or_insert(id);
+    }
+}
+
+/// Forgets that `value` was interned into the interner `id` once it no longer holds it, in debug
+/// builds only.
+pub(super) fn forget<T: ?Sized + 'static>(value: &T, id: InternerId) {
+    if cfg!(debug_assertions) {
+        let (key, mut shard) = shard(value);
+        if shard.get(&key) == Some(&id) {
+            shard.remove(&key);
+        }
+    }
+}
+
+/// Returns the interner `value` was first interned into, in debug builds only.
+pub(super) fn lookup<T: ?Sized + 'static>(value: &T) -> Option<InternerId> {
+    if cfg!(debug_assertions) {
+        let (key, shard) = shard(value);
+        shard.get(&key).copied()
+    } else {
+        None
+    }
+}
@@ -0,0 +1,262 @@
+use std::any::{type_name, Any};
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::mem::ManuallyDrop;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError, Weak};
+
+use hashbrown::HashTable;
+
+use super::{DefaultHashBuilder, Internable, ToBoxed};
+
+/// An interner which frees values once the last handle to them is dropped, instead of leaking
+/// them.
+///
+/// Values are held by [`Shared`] handles, which are reference counted. Dropping the last handle
+/// to a value removes it from the interner and deallocates it, so interning it again creates a
+/// new value.
+pub struct RcInterner<T: ?Sized + 'static, S = DefaultHashBuilder> {
+    entries: OnceLock<Mutex<RcEntries<T, S>>>,
+    /// The callbacks registered with [`RcInterner::on_evict`].
+    on_evict: Mutex<Vec<Arc<EvictCallback<T>>>>,
+}
+
+/// A callback invoked with every value removed from an [`RcInterner`].
+type EvictCallback<T> = dyn Fn(&T) + Send + Sync;
+
+struct RcEntries<T: ?Sized, S> {
+    hasher: S,
+    values: HashTable<RcEntry<T>>,
+}
+
+/// A live value of an [`RcInterner`].
+struct RcEntry<T: ?Sized> {
+    /// The hash of the value, so the table can be rehashed without upgrading it.
+    hash: u64,
+    value: Weak<T>,
+    /// The data attached by [`RcInterner::intern_with_init`], dropped with the entry.
+    side: Option<Arc<dyn Any + Send + Sync>>,
+}
+
+impl<T: ?Sized, S> Default for RcInterner<T, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized, S> RcInterner<T, S> {
+    pub const fn new() -> Self {
+        Self {
+            entries: OnceLock::new(),
+            on_evict: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Registers `callback` to be called with every value removed from the interner once its last
+    /// handle is dropped, so caches keyed on the value can purge their entries.
+    ///
+    /// Callbacks are called on the thread dropping the last handle, after the value has been
+    /// removed from the interner but before it is freed.
+    pub fn on_evict(&self, callback: impl Fn(&T) + Send + Sync + 'static) {
+        self.on_evict
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Arc::new(callback));
+    }
+
+    /// Calls the callbacks registered with [`RcInterner::on_evict`] with `value`.
+    fn evicted(&self, value: &T) {
+        let callbacks = self
+            .on_evict
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        for callback in callbacks {
+            callback(value);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, RcEntries<T, S>>
+    where
+        S: Default,
+    {
+        self.entries
+            .get_or_init(|| {
+                Mutex::new(RcEntries {
+                    hasher: S::default(),
+                    values: HashTable::new(),
+                })
+            })
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Internable + ToBoxed + ?Sized, S: BuildHasher + Default> RcInterner<T, S> {
+    /// Returns a [`Shared`] handle equal to `value`, allocating a copy of `value` if no live
+    /// value is equal to it.
+    #[must_use]
+    pub fn intern(&self, value: &T) -> Shared<'_, T, S> {
+        self.intern_entry(value, |_| ()).0
+    }
+
+    /// Like [`RcInterner::intern`], but also returns data attached to the value, which `init`
+    /// creates when the value has none yet.
+    ///
+    /// `init` runs under the lock of the interner, so it runs once per distinct live value even
+    /// when several threads intern the value at once, and must not use the interner. The data is
+    /// dropped when the value is removed from the interner.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data attached to the value is not a `D`.
+    #[must_use]
+    pub fn intern_with_init<D: Any + Send + Sync>(
+        &self,
+        value: &T,
+        init: impl FnOnce() -> D,
+    ) -> (Shared<'_, T, S>, Arc<D>) {
+        self.intern_entry(value, |entry| {
+            let side = entry.side.get_or_insert_with(|| Arc::new(init()));
+            Arc::clone(side).downcast().unwrap_or_else(|_| {
+                panic!("data attached to the value is not a `{}`", type_name::<D>())
+            })
+        })
+    }
+
+    /// Returns the data attached to `value` by [`RcInterner::intern_with_init`], or `None` if it
+    /// has none or the data is not a `D`.
+    pub fn side_data<D: Any + Send + Sync>(&self, value: &Shared<'_, T, S>) -> Option<Arc<D>> {
+        let entries = self.lock();
+        let hash = entries.hasher.hash_one(&**value);
+        let entry = entries.values.find(hash, |entry| {
+            std::ptr::addr_eq(entry.value.as_ptr(), Arc::as_ptr(&value.value))
+        })?;
+        Arc::clone(entry.side.as_ref()?).downcast().ok()
+    }
+
+    /// Returns a [`Shared`] handle equal to `value` like [`RcInterner::intern`], with the result
+    /// of calling `f` on its entry under the lock.
+    fn intern_entry<R>(
+        &self,
+        value: &T,
+        f: impl FnOnce(&mut RcEntry<T>) -> R,
+    ) -> (Shared<'_, T, S>, R) {
+        let mut entries = self.lock();
+        let RcEntries { hasher, values } = &mut *entries;
+        let hash = hasher.hash_one(value);
+        let existing = values.find_mut(hash, |entry| {
+            entry
+                .value
+                .upgrade()
+                .is_some_and(|existing| *existing == *value)
+        });
+        let (value, result) = match existing {
+            Some(entry) => {
+                // Handles are only dropped under the lock, so the value is still alive.
+                let value = entry.value.upgrade().expect("interned value was dropped");
+                (value, f(entry))
+            }
+            None => {
+                let value: Arc<T> = value.to_boxed().into();
+                let mut entry = RcEntry {
+                    hash,
+                    value: Arc::downgrade(&value),
+                    side: None,
+                };
+                let result = f(&mut entry);
+                values.insert_unique(hash, entry, |entry| entry.hash);
+                (value, result)
+            }
+        };
+        let shared = Shared {
+            value: ManuallyDrop::new(value),
+            interner: self,
+        };
+        (shared, result)
+    }
+
+    /// Returns the number of live values.
+    pub fn len(&self) -> usize {
+        self.lock().values.len()
+    }
+
+    /// Returns `true` if there are no live values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A reference counted value interned by a [`RcInterner`].
+///
+/// Like [`Interned<T>`](super::Interned), equality and hashing use the address of the value.
+pub struct Shared<
+    'a,
+    T: Internable + ?Sized + 'static,
+    S: BuildHasher + Default = DefaultHashBuilder,
+> {
+    value: ManuallyDrop<Arc<T>>,
+    interner: &'a RcInterner<T, S>,
+}
+
+impl<T: Internable + ?Sized, S: BuildHasher + Default> Drop for Shared<'_, T, S> {
+    fn drop(&mut self) {
+        let mut entries = self.interner.lock();
+        // SAFETY: `value` is not used after this.
+        let value = unsafe { ManuallyDrop::take(&mut self.value) };
+        // The count is only decremented under the lock, and new handles are only created under the
+        // lock or from existing handles, so no other handle can appear once this is the last one.
+        if Arc::strong_count(&value) == 1 {
+            let RcEntries { hasher, values } = &mut *entries;
+            let hash = hasher.hash_one(&*value);
+            let removed = values
+                .find_entry(hash, |entry| {
+                    std::ptr::addr_eq(entry.value.as_ptr(), Arc::as_ptr(&value))
+                })
+                .ok()
+                .map(|entry| entry.remove().0);
+            // Drop the value and its data without holding the lock, in case dropping them interns.
+            drop(entries);
+            self.interner.evicted(&value);
+            drop(removed);
+        }
+        drop(value);
+    }
+}
+
+impl<T: Internable + ?Sized, S: BuildHasher + Default> Deref for Shared<'_, T, S> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T: Internable + ?Sized, S: BuildHasher + Default> Clone for Shared<'_, T, S> {
+    fn clone(&self) -> Self {
+        Self {
+            value: ManuallyDrop::new(Arc::clone(&self.value)),
+            interner: self.interner,
+        }
+    }
+}
+
+impl<T: Internable + ?Sized, S: BuildHasher + Default> PartialEq for Shared<'_, T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value.ref_eq(&other.value)
+    }
+}
+
+impl<T: Internable + ?Sized, S: BuildHasher + Default> Eq for Shared<'_, T, S> {}
+
+impl<T: Internable + ?Sized, S: BuildHasher + Default> Hash for Shared<'_, T, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.ref_hash(state);
+    }
+}
+
+impl<T: Internable + ?Sized + Debug, S: BuildHasher + Default> Debug for Shared<'_, T, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
@@ -0,0 +1,42 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use super::Interner;
+
+/// The interners shared by every copy of a label trait, by the path of the trait.
+///
+/// Every statically linked copy of this crate has its own registry, see [`global_interner`].
+static REGISTRY: OnceLock<Mutex<HashMap<&'static str, &'static (dyn Any + Send + Sync)>>> =
+    OnceLock::new();
+
+/// Returns the interner registered under `name`, registering a new one if there is none.
+///
+/// A crate defining a label trait can be linked into several dynamic libraries, each with its own
+/// statics. Looking the interner up by the path of the trait makes every copy of the trait intern
+/// into the same set, so [`Interned`](super::Interned) values keep comparing equal across library
+/// boundaries.
+///
+/// This only holds if the registry itself has a single copy, so `labels` must be linked
+/// dynamically and shared by every library, for example by building it as a `dylib` with
+/// `-C prefer-dynamic`. A library which links `labels` statically embeds its own registry, and
+/// its labels intern into a set of their own again.
+///
+/// # Panics
+///
+/// Panics if an interner of another type was registered under `name`.
+pub fn global_interner<T: ?Sized + 'static>(name: &'static str) -> &'static Interner<T>
+where
+    Interner<T>: Send + Sync,
+{
+    let mut registry = REGISTRY
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let interner = *registry
+        .entry(name)
+        .or_insert_with(|| Box::leak(Box::new(Interner::<T>::new())));
+    interner
+        .downcast_ref()
+        .unwrap_or_else(|| panic!("an interner of another type is registered as `{name}`"))
+}
@@ -0,0 +1,29 @@
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{GlobalInternable, Interned};
+
+/// Serializes the interned value itself, so interned fields look like plain ones.
+impl<T: ?Sized + Serialize> Serialize for Interned<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+/// Deserializes an owned value and interns it into the
+/// [global interner](GlobalInternable::global_interner) of its type, so the result is equal to
+/// handles interned there before.
+///
+/// Fails if the value was not interned before and cannot be interned, see
+/// [`Interner::try_intern`](super::Interner::try_intern).
+impl<'de, T: ?Sized + GlobalInternable> Deserialize<'de> for Interned<T>
+where
+    Box<T>: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Box::<T>::deserialize(deserializer)?;
+        T::global_interner()
+            .try_intern_owned(value)
+            .map_err(D::Error::custom)
+    }
+}
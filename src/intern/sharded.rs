@@ -0,0 +1,258 @@
+use std::hash::BuildHasher;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+use super::{DefaultHashBuilder, InternError, Internable, Interned, Interner};
+
+/// The number of shards of a [`ShardedInterner`].
+const SHARDS: usize = 32;
+
+/// The shift taking the shard from the high bits of a hash, below the top seven bits which the
+/// hash table of a shard keeps as a tag for every value.
+const SHARD_SHIFT: u32 = 64 - 7 - SHARDS.trailing_zeros();
+
+/// An interner which spreads its values over several [`Interner`]s selected by hash, so threads
+/// interning new values into different shards do not wait on the same write lock.
+///
+/// Values are hashed once with a randomly seeded hasher shared by every shard, which picks the
+/// shard and probes it.
+///
+/// Values are only ordered by first intern within their shard, so there is no `to_vec`.
+pub struct ShardedInterner<T: ?Sized + 'static> {
+    shards: [Interner<T>; SHARDS],
+    /// Counts the values inserted into each shard, read by [`ShardedInterner::stats`] without
+    /// locking.
+    counters: [ShardCounters; SHARDS],
+    /// The hasher of every shard, created on the first use of a shard.
+    hasher: OnceLock<DefaultHashBuilder>,
+}
+
+/// The values inserted into one shard of a [`ShardedInterner`].
+struct ShardCounters {
+    entries: AtomicUsize,
+    bytes: AtomicUsize,
+}
+
+/// Statistics about a [`ShardedInterner`], summed over its shards without locking them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ShardedStats {
+    /// The number of distinct values held by the interner.
+    pub entries: usize,
+    /// The total size of the values held by the interner, in bytes.
+    pub bytes: usize,
+    /// The number of lookups which found an interned value.
+    pub hits: usize,
+    /// The number of lookups which found no interned value.
+    pub misses: usize,
+}
+
+impl<T: ?Sized> Default for ShardedInterner<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized> ShardedInterner<T> {
+    /// The number of shards values are spread over.
+    pub const SHARDS: usize = SHARDS;
+
+    pub const fn new() -> Self {
+        Self {
+            shards: [const { Interner::new() }; SHARDS],
+            counters: [const {
+                ShardCounters {
+                    entries: AtomicUsize::new(0),
+                    bytes: AtomicUsize::new(0),
+                }
+            }; SHARDS],
+            hasher: OnceLock::new(),
+        }
+    }
+
+    /// Seals every shard, see [`Interner::seal`].
+    pub fn seal(&self) {
+        for shard in &self.shards {
+            shard.seal();
+        }
+    }
+
+    /// Returns `true` if the interner has been [sealed](ShardedInterner::seal).
+    pub fn is_sealed(&self) -> bool {
+        self.shards[0].is_sealed()
+    }
+
+    /// Returns statistics about the values held by the interner and the lookups made so far.
+    ///
+    /// The counters of each shard are summed without locking any shard, so the statistics are
+    /// eventually consistent: while other threads intern values, they may miss some of those
+    /// values or count a value before the lookup that inserted it. Once interning stops they are
+    /// exact.
+    pub fn stats(&self) -> ShardedStats {
+        let mut stats = ShardedStats::default();
+        for (shard, counters) in self.shards.iter().zip(&self.counters) {
+            let (hits, misses) = shard.lookups();
+            stats.entries += counters.entries.load(Ordering::Relaxed);
+            stats.bytes += counters.bytes.load(Ordering::Relaxed);
+            stats.hits += hits;
+            stats.misses += misses;
+        }
+        stats
+    }
+}
+
+impl<T: Internable + ?Sized> ShardedInterner<T> {
+    /// Returns an [`Interned<T>`] equal to `value`, leaking a copy of `value` if it was not
+    /// interned before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`].
+    #[must_use]
+    pub fn intern(&self, value: &T) -> Interned<T> {
+        match self.try_intern(value) {
+            Ok(interned) => interned,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Like [`ShardedInterner::intern`], but returns an error instead of panicking if `value`
+    /// cannot be interned.
+    pub fn try_intern(&self, value: &T) -> Result<Interned<T>, InternError> {
+        let hash = self.hasher().hash_one(value);
+        let index = shard_index(hash);
+        let (interned, inserted) = self.shards[index].try_intern_inserted_hashed(hash, value)?;
+        if inserted {
+            let counters = &self.counters[index];
+            counters.entries.fetch_add(1, Ordering::Relaxed);
+            counters
+                .bytes
+                .fetch_add(std::mem::size_of_val(value), Ordering::Relaxed);
+        }
+        Ok(interned)
+    }
+
+    /// Reserves capacity for at least `additional_each` more values in every shard.
+    pub fn reserve_per_shard(&self, additional_each: usize) {
+        self.hasher();
+        for shard in &self.shards {
+            shard.reserve(additional_each);
+        }
+    }
+
+    /// Returns the number of values held by each shard, to observe how evenly values are spread.
+    pub fn shard_lens(&self) -> [usize; SHARDS] {
+        std::array::from_fn(|index| self.shards[index].len())
+    }
+
+    /// Returns the hasher shared by the shards, giving it to every shard on the first call.
+    ///
+    /// This must be called before a shard hashes a value, so no shard creates its own hasher.
+    fn hasher(&self) -> &DefaultHashBuilder {
+        self.hasher.get_or_init(|| {
+            let hasher = DefaultHashBuilder::default();
+            for shard in &self.shards {
+                shard.hasher.get_or_init(|| hasher.clone());
+            }
+            hasher
+        })
+    }
+}
+
+/// Returns the index of the shard holding the values with the given hash.
+fn shard_index(hash: u64) -> usize {
+    (hash >> SHARD_SHIFT) as usize % SHARDS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shard_lens_reflect_skew() {
+        let interner: ShardedInterner<str> = ShardedInterner::new();
+        interner.reserve_per_shard(8);
+        let values: Vec<String> = (0..10_000).map(|i| format!("value_{i}")).collect();
+        let hot: Vec<&str> = values
+            .iter()
+            .map(String::as_str)
+            .filter(|value| shard_index(interner.hasher().hash_one(value)) == 3)
+            .take(100)
+            .collect();
+        for value in hot.iter().chain(&["a", "b", "c", "d"]) {
+            let _ = interner.intern(value);
+        }
+        let _ = interner.intern(hot[0]);
+
+        let lens = interner.shard_lens();
+        assert_eq!(lens.iter().sum::<usize>(), 104);
+        assert!(lens[3] >= 100);
+        assert_eq!(lens.iter().max(), Some(&lens[3]));
+    }
+
+    #[test]
+    fn shards_are_probed_with_the_shared_hash() {
+        let interner: ShardedInterner<str> = ShardedInterner::new();
+        let values: Vec<String> = (0..1_000).map(|i| format!("value_{i}")).collect();
+        for value in &values {
+            let _ = interner.intern(value);
+        }
+
+        let hasher = interner.hasher();
+        for value in &values {
+            let hash = hasher.hash_one(value.as_str());
+            let shard = &interner.shards[shard_index(hash)];
+            assert_eq!(shard.hasher().hash_one(value.as_str()), hash);
+            assert!(shard.get(value).is_some());
+        }
+        let lens = interner.shard_lens();
+        assert!(lens.iter().all(|&len| len > 0), "{lens:?}");
+    }
+
+    #[test]
+    fn stats_converge_to_distinct_count() {
+        let interner: ShardedInterner<str> = ShardedInterner::new();
+        let values: Vec<String> = (0..2_000).map(|i| format!("value_{i}")).collect();
+        let done = std::sync::atomic::AtomicBool::new(false);
+
+        std::thread::scope(|scope| {
+            let writers: Vec<_> = (0..8)
+                .map(|thread| {
+                    let values = &values;
+                    let interner = &interner;
+                    scope.spawn(move || {
+                        for value in values.iter().skip(thread * 100).step_by(3) {
+                            let _ = interner.intern(value);
+                        }
+                        for value in values.iter().rev() {
+                            let _ = interner.intern(value);
+                        }
+                    })
+                })
+                .collect();
+            scope.spawn(|| {
+                let mut last = 0;
+                while !done.load(Ordering::Relaxed) {
+                    let entries = interner.stats().entries;
+                    assert!(entries >= last && entries <= values.len());
+                    last = entries;
+                }
+            });
+            for writer in writers {
+                writer.join().unwrap();
+            }
+            done.store(true, Ordering::Relaxed);
+        });
+
+        let stats = interner.stats();
+        assert_eq!(stats.entries, values.len());
+        assert_eq!(stats.entries, interner.shard_lens().iter().sum::<usize>());
+        assert_eq!(stats.bytes, values.iter().map(String::len).sum::<usize>());
+        let lookups: usize = (0..8)
+            .map(|thread| values.iter().skip(thread * 100).step_by(3).count() + values.len())
+            .sum();
+        assert_eq!(stats.hits + stats.misses, lookups);
+        assert!(stats.misses >= stats.entries);
+    }
+}
@@ -0,0 +1,58 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use serde::ser::{Error, SerializeTuple};
+use serde::{Serialize, Serializer};
+
+use super::Interned;
+
+/// The strings collected while serializing a [`SerializeWithTable`].
+#[derive(Default)]
+struct StringTable {
+    ids: HashMap<Interned<str>, u32>,
+    strings: Vec<&'static str>,
+}
+
+thread_local! {
+    static TABLE: RefCell<Option<StringTable>> = const { RefCell::new(None) };
+}
+
+/// Serializes a value followed by a table of the interned strings it contains.
+///
+/// Fields serialized with [`serialize_as_table_id`] are written as a `u32` index into the table,
+/// so every distinct string is only written once. The output is a tuple of the value and the
+/// table of strings.
+pub struct SerializeWithTable<'a, T: ?Sized>(pub &'a T);
+
+impl<T: ?Sized + Serialize> Serialize for SerializeWithTable<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        let previous = TABLE.replace(Some(StringTable::default()));
+        let result = tuple.serialize_element(self.0);
+        let table = TABLE.replace(previous).unwrap_or_default();
+        result?;
+        tuple.serialize_element(&table.strings)?;
+        tuple.end()
+    }
+}
+
+/// Serializes an [`Interned<str>`] as its id in the table of the enclosing
+/// [`SerializeWithTable`], for use with `#[serde(serialize_with = "...")]`.
+pub fn serialize_as_table_id<S: Serializer>(
+    value: &Interned<str>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let id = TABLE.with_borrow_mut(|table| {
+        let table = table.as_mut()?;
+        Some(*table.ids.entry(*value).or_insert_with(|| {
+            table.strings.push(value.0);
+            (table.strings.len() - 1) as u32
+        }))
+    });
+    match id {
+        Some(id) => serializer.serialize_u32(id),
+        None => Err(S::Error::custom(
+            "interned string serialized as a table id outside of `SerializeWithTable`",
+        )),
+    }
+}
@@ -0,0 +1,31 @@
+// The lock used by `Interner`, which is `parking_lot`'s with the `parking_lot` feature and the
+// standard library's otherwise.
+
+#[cfg(feature = "parking_lot")]
+pub(super) use parking_lot::{RwLock, RwLockWriteGuard};
+#[cfg(not(feature = "parking_lot"))]
+pub(super) use std_lock::{RwLock, RwLockWriteGuard};
+
+#[cfg(not(feature = "parking_lot"))]
+mod std_lock {
+    use std::sync::{self, PoisonError};
+
+    pub(in super::super) use std::sync::{RwLockReadGuard, RwLockWriteGuard};
+
+    /// A [`std::sync::RwLock`] which ignores poisoning, like `parking_lot::RwLock`.
+    pub(in super::super) struct RwLock<T>(sync::RwLock<T>);
+
+    impl<T> RwLock<T> {
+        pub(in super::super) fn new(value: T) -> Self {
+            Self(sync::RwLock::new(value))
+        }
+
+        pub(in super::super) fn read(&self) -> RwLockReadGuard<'_, T> {
+            self.0.read().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        pub(in super::super) fn write(&self) -> RwLockWriteGuard<'_, T> {
+            self.0.write().unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::{Internable, Interner};
+
+/// The number of values cached per interner and thread.
+const VALUES_PER_INTERNER: usize = 8;
+
+/// The number of interners a thread caches values for.
+const INTERNERS: usize = 8;
+
+thread_local! {
+    /// The most recently used values of the most recently used interners, most recent first.
+    static CACHE: RefCell<Vec<(u64, Box<dyn Any>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Allocates the keys of interner caches, zero means no key was allocated yet.
+static NEXT_KEY: AtomicU64 = AtomicU64::new(1);
+
+impl<T: Internable + ?Sized, S> Interner<T, S> {
+    /// Returns the value equal to `value` from the cache of the current thread, given the
+    /// [key](Interner::cache_key) of the cache.
+    pub(super) fn cached(&self, key: u64, value: &T) -> Option<&'static T> {
+        CACHE.with_borrow_mut(|cache| {
+            let values = cache
+                .iter_mut()
+                .find(|(cache_key, _)| *cache_key == key)?
+                .1
+                .downcast_mut::<Vec<&'static T>>()?;
+            let index = values.iter().position(|cached| **cached == *value)?;
+            let cached = values.remove(index);
+            values.insert(0, cached);
+            Some(cached)
+        })
+    }
+
+    /// Adds `value` to the cache of the current thread, given the [key](Interner::cache_key) of
+    /// the cache when `value` was looked up.
+    pub(super) fn cache(&self, key: u64, value: &'static T) {
+        CACHE.with_borrow_mut(|cache| {
+            let index = match cache.iter().position(|(cache_key, _)| *cache_key == key) {
+                Some(index) => index,
+                None => {
+                    cache.truncate(INTERNERS - 1);
+                    cache.push((key, Box::new(Vec::<&'static T>::new())));
+                    cache.len() - 1
+                }
+            };
+            let entry = cache.remove(index);
+            cache.insert(0, entry);
+            if let Some(values) = cache[0].1.downcast_mut::<Vec<&'static T>>() {
+                values.truncate(VALUES_PER_INTERNER - 1);
+                values.insert(0, value);
+            }
+        });
+    }
+
+    /// Invalidates the caches of all threads for this interner.
+    pub(super) fn invalidate_cache(&self) {
+        self.cache_key
+            .store(NEXT_KEY.fetch_add(1, Ordering::Relaxed), Ordering::Release);
+    }
+
+    /// Returns the key of the caches for this interner, which changes when they are invalidated.
+    pub(super) fn cache_key(&self) -> u64 {
+        let key = self.cache_key.load(Ordering::Acquire);
+        if key != 0 {
+            return key;
+        }
+        let new = NEXT_KEY.fetch_add(1, Ordering::Relaxed);
+        match self
+            .cache_key
+            .compare_exchange(0, new, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => new,
+            Err(key) => key,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intern::InternByClone;
+
+    #[derive(Clone, Debug)]
+    struct Keyed {
+        key: u32,
+        detail: &'static str,
+    }
+
+    impl PartialEq for Keyed {
+        fn eq(&self, other: &Self) -> bool {
+            self.key == other.key
+        }
+    }
+
+    impl Eq for Keyed {}
+
+    impl std::hash::Hash for Keyed {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            self.key.hash(state);
+        }
+    }
+
+    impl InternByClone for Keyed {}
+
+    #[test]
+    fn recently_found_values_are_cached() {
+        let interner: Interner<str> = Interner::new();
+        let values: Vec<String> = (0..VALUES_PER_INTERNER + 1)
+            .map(|i| format!("value_{i}"))
+            .collect();
+        let interned: Vec<_> = values.iter().map(|value| interner.intern(value)).collect();
+        let key = interner.cache_key();
+        assert_eq!(interner.cached(key, "value_0"), None);
+        for value in &values {
+            let _ = interner.intern(value);
+        }
+
+        assert_eq!(interner.cached(key, "value_0"), None);
+        for (value, interned) in values.iter().zip(&interned).skip(1) {
+            assert!(std::ptr::eq(
+                interner.cached(key, value).unwrap(),
+                interned.0
+            ));
+        }
+        std::thread::scope(|scope| {
+            scope.spawn(|| assert_eq!(interner.cached(key, "value_1"), None));
+        });
+    }
+
+    #[test]
+    fn upgrades_invalidate_the_cache() {
+        let interner: Interner<Keyed> = Interner::new();
+        let key = Keyed {
+            key: 1,
+            detail: "old",
+        };
+        let _ = interner.intern(&key);
+        let _ = interner.intern(&key);
+        assert!(interner.cached(interner.cache_key(), &key).is_some());
+
+        let _ = interner.upgrade(
+            &key,
+            Box::new(Keyed {
+                key: 1,
+                detail: "new",
+            }),
+        );
+        assert_eq!(interner.cached(interner.cache_key(), &key), None);
+        assert_eq!(interner.intern(&key).detail, "new");
+        assert_eq!(interner.get(&key).unwrap().detail, "new");
+    }
+
+    #[test]
+    #[cfg(feature = "clear")]
+    fn clearing_invalidates_the_cache() {
+        let interner: Interner<str> = Interner::new();
+        let old = interner.intern("value");
+        let _ = interner.intern("value");
+        interner.clear();
+
+        assert_eq!(interner.get("value"), None);
+        assert!(!interner.intern("value").ptr_eq(&old));
+    }
+
+    #[test]
+    fn caches_of_many_interners() {
+        let interners: Vec<Interner<u32>> = (0..INTERNERS * 2).map(|_| Interner::new()).collect();
+        for (i, interner) in interners.iter().enumerate() {
+            let _ = interner.intern(&(i as u32));
+            let _ = interner.intern(&(i as u32));
+        }
+        let cached: Vec<bool> = interners
+            .iter()
+            .enumerate()
+            .map(|(i, interner)| interner.cached(interner.cache_key(), &(i as u32)).is_some())
+            .collect();
+        assert!(cached
+            .iter()
+            .enumerate()
+            .all(|(i, &cached)| cached == (i >= INTERNERS)));
+        for (i, interner) in interners.iter().enumerate() {
+            assert_eq!(*interner.intern(&(i as u32)), i as u32);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::hash::BuildHasher;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+use hashbrown::HashTable;
+
+use super::{DefaultHashBuilder, GcInterned, Internable, ToBoxed};
+
+/// An interner whose values expire once they have not been interned for a while, removed by an
+/// explicit [`sweep`](TtlInterner::sweep).
+///
+/// Like with [`GcInterner`](super::GcInterner), values are held by reference counted
+/// [`GcInterned`] handles. A value is only removed once it has not been interned for the time to
+/// live of the interner and no handle to it is left, so churning sets of values which are never
+/// fully dropped at once stay bounded.
+pub struct TtlInterner<T: ?Sized + 'static, S = DefaultHashBuilder> {
+    ttl: Duration,
+    entries: OnceLock<Mutex<TtlEntries<T, S>>>,
+}
+
+struct TtlEntries<T: ?Sized, S> {
+    hasher: S,
+    values: HashTable<TtlEntry<T>>,
+}
+
+/// A value of a [`TtlInterner`] with the last time it was interned.
+struct TtlEntry<T: ?Sized> {
+    value: Arc<T>,
+    last_access: Instant,
+}
+
+impl<T: ?Sized, S> TtlInterner<T, S> {
+    /// Creates an interner whose values expire once they have not been interned for `ttl`.
+    pub const fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: OnceLock::new(),
+        }
+    }
+
+    /// Returns the time after which values which were not interned expire.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TtlEntries<T, S>>
+    where
+        S: Default,
+    {
+        self.entries
+            .get_or_init(|| {
+                Mutex::new(TtlEntries {
+                    hasher: S::default(),
+                    values: HashTable::new(),
+                })
+            })
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Removes and frees every value which was last interned at least the time to live before
+    /// `now` and has no live handles, returning how many were removed.
+    pub fn sweep(&self, now: Instant) -> usize
+    where
+        S: Default,
+    {
+        let mut entries = self.lock();
+        let len = entries.values.len();
+        // Handles can only be created under the lock or from other handles, so a value without
+        // handles cannot gain one while it is being removed.
+        entries.values.retain(|entry| {
+            now.saturating_duration_since(entry.last_access) < self.ttl
+                || Arc::strong_count(&entry.value) > 1
+        });
+        len - entries.values.len()
+    }
+
+    /// Returns the number of values held, including expired ones not yet
+    /// [swept](TtlInterner::sweep).
+    pub fn len(&self) -> usize
+    where
+        S: Default,
+    {
+        self.lock().values.len()
+    }
+
+    /// Returns `true` if the interner holds no values.
+    pub fn is_empty(&self) -> bool
+    where
+        S: Default,
+    {
+        self.len() == 0
+    }
+}
+
+impl<T: Internable + ToBoxed + ?Sized, S: BuildHasher + Default> TtlInterner<T, S> {
+    /// Returns a [`GcInterned`] equal to `value`, allocating a copy of `value` if the interner
+    /// holds no equal value, and marks it as accessed now.
+    #[must_use]
+    pub fn intern(&self, value: &T) -> GcInterned<T> {
+        self.intern_at(value, Instant::now())
+    }
+
+    /// Like [`TtlInterner::intern`], but marks the value as accessed at `now`.
+    #[must_use]
+    pub fn intern_at(&self, value: &T, now: Instant) -> GcInterned<T> {
+        let mut entries = self.lock();
+        let TtlEntries { hasher, values } = &mut *entries;
+        let hash = hasher.hash_one(value);
+        if let Some(existing) = values.find_mut(hash, |entry| *entry.value == *value) {
+            existing.last_access = existing.last_access.max(now);
+            return GcInterned(Arc::clone(&existing.value));
+        }
+        let value: Arc<T> = value.to_boxed().into();
+        let entry = TtlEntry {
+            value: Arc::clone(&value),
+            last_access: now,
+        };
+        values.insert_unique(hash, entry, |entry| hasher.hash_one(&*entry.value));
+        GcInterned(value)
+    }
+}
@@ -1,8 +1,12 @@
 pub mod intern;
+pub mod symbol;
 
 use std::any::Any;
 use std::hash::{Hash, Hasher};
+use std::ops::{Deref, DerefMut};
 
+#[cfg(feature = "derive")]
+pub use labels_derive::parent;
 #[doc(hidden)]
 pub use paste as __paste;
 
@@ -22,6 +26,9 @@ pub trait DynHash: DynEq {
 
     /// Feeds this value into the given [`Hasher`].
     fn dyn_hash(&self, state: &mut dyn Hasher);
+
+    /// Feeds this value into the given [`Hasher`] without its [`TypeId`](std::any::TypeId).
+    fn dyn_hash_value(&self, state: &mut dyn Hasher);
 }
 
 impl<T: Any + Eq> DynEq for T {
@@ -46,11 +53,286 @@ impl<T: DynEq + Hash> DynHash for T {
         self.type_id().hash(&mut state);
         T::hash(self, &mut state);
     }
+
+    fn dyn_hash_value(&self, mut state: &mut dyn Hasher) {
+        T::hash(self, &mut state);
+    }
+}
+
+/// A [`Hasher`] whose output only depends on the data written to it, unlike the randomly seeded
+/// hashers of the standard library.
+///
+/// This is the 64-bit FNV-1a hash, used by the generated `stable_hash` methods.
+#[derive(Clone, Copy, Debug)]
+pub struct StableHasher(u64);
+
+impl StableHasher {
+    /// Creates a new `StableHasher`.
+    pub const fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
 }
 
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// A field wrapper for incidental label data, such as a source span, which should not affect the
+/// identity of the label.
+///
+/// All `Ignored` values compare equal and feed nothing to a [`Hasher`], so derived [`PartialEq`]
+/// and [`Hash`] implementations skip the field. Labels which only differ in ignored fields are
+/// interned as one value, keeping the data of the first one interned.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Ignored<T>(pub T);
+
+impl<T> PartialEq for Ignored<T> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<T> Eq for Ignored<T> {}
+
+impl<T> Hash for Ignored<T> {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+impl<T> Deref for Ignored<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Ignored<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Defines a label trait, with an interner for its trait objects.
+///
+/// Flags after the name of the trait, separated by `;`, opt into more generated methods:
+///
+/// - `ord_by_first_intern` orders labels by the sequence in which they were first interned.
+/// - `hierarchy` generates `parent` and `is_descendant_of`, which follow the parents declared
+///   with the `#[labels::parent]` attribute of the `derive` feature.
+/// - `stable_hash` generates `type_tag` and `stable_hash`, which identify labels across
+///   compilations.
+/// - `interned` generates an `interned()` constructor for labels implementing `Default`.
+///
+/// The methods are opt-in, so a trait without the flag can declare `extra_methods` of the same
+/// names.
+///
+/// Discarding the result of the generated `intern` method is a mistake, so it is linted by
+/// `unused_must_use`:
+///
+/// ```compile_fail
+/// #![deny(unused_must_use)]
+/// labels::define_label!(MyLabel);
+///
+/// #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// struct A;
+///
+/// impl MyLabel for A {
+///     fn dyn_clone(&self) -> Box<dyn MyLabel> {
+///         Box::new(self.clone())
+///     }
+///
+///     fn as_dyn_eq(&self) -> &dyn labels::DynEq {
+///         self
+///     }
+///
+///     fn as_dyn_hash(&self) -> &dyn labels::DynHash {
+///         self
+///     }
+/// }
+///
+/// A.intern();
+/// ```
 #[macro_export]
 macro_rules! define_label {
-    ($(#[$attr:meta])* $vis:vis $label_name:ident $(;)? $(
+    (
+        @with [$($trait_items:tt)*] [$($impl_items:tt)*]
+        $(#[$attr:meta])* $vis:vis $label_name:ident; ord_by_first_intern $(; $($rest:tt)*)?
+    ) => {
+        $crate::define_label!(
+            @with [$($trait_items)*] [$($impl_items)*]
+            $(#[$attr])* $vis $label_name $(; $($rest)*)?
+        );
+
+        impl PartialOrd for dyn $label_name {
+            fn partial_cmp(&self, other: &Self) -> ::std::option::Option<::std::cmp::Ordering> {
+                ::std::option::Option::Some(self.cmp(other))
+            }
+        }
+
+        /// Orders labels by the sequence in which they were first interned, without interning
+        /// them. Labels which were not interned yet sort after interned ones, by a hash of their
+        /// type and value and then by address.
+        impl Ord for dyn $label_name {
+            fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+                $crate::__paste::paste! {
+                    let interner = &[<$label_name:upper _INTERNER>];
+                }
+                match (
+                    interner.__first_intern_index_uncounted(self),
+                    interner.__first_intern_index_uncounted(other),
+                ) {
+                    (::std::option::Option::Some(a), ::std::option::Option::Some(b)) => a.cmp(&b),
+                    (::std::option::Option::Some(_), ::std::option::Option::None) => {
+                        ::std::cmp::Ordering::Less
+                    }
+                    (::std::option::Option::None, ::std::option::Option::Some(_)) => {
+                        ::std::cmp::Ordering::Greater
+                    }
+                    (::std::option::Option::None, ::std::option::Option::None) if self == other => {
+                        ::std::cmp::Ordering::Equal
+                    }
+                    (::std::option::Option::None, ::std::option::Option::None) => {
+                        let hash = |label: &Self| {
+                            let mut state = $crate::StableHasher::new();
+                            label.as_dyn_hash().dyn_hash(&mut state);
+                            ::std::hash::Hasher::finish(&state)
+                        };
+                        hash(self).cmp(&hash(other)).then_with(|| {
+                            (self as *const Self)
+                                .cast::<()>()
+                                .cmp(&(other as *const Self).cast::<()>())
+                        })
+                    }
+                }
+            }
+        }
+    };
+    (
+        @with [$($trait_items:tt)*] [$($impl_items:tt)*]
+        $(#[$attr:meta])* $vis:vis $label_name:ident; hierarchy $(; $($rest:tt)*)?
+    ) => {
+        $crate::define_label!(
+            @with [
+                $($trait_items)*
+
+                /// Returns the parent of this `
+                #[doc = stringify!($label_name)]
+                /// ` in a label hierarchy, as declared with the `#[labels::parent]` attribute
+                /// and registered when the label was first interned.
+                fn parent(&self) -> ::std::option::Option<$crate::intern::Interned<dyn $label_name>>
+                where
+                    Self: Sized,
+                {
+                    $crate::__paste::paste! {
+                        [<$label_name:upper _PARENTS>].get($label_name::intern(self))
+                    }
+                }
+
+                /// Returns `true` if `other` is an ancestor of this `
+                #[doc = stringify!($label_name)]
+                /// `, following the chain of [`parent`](Self::parent)s.
+                fn is_descendant_of(&self, other: &dyn $label_name) -> bool
+                where
+                    Self: Sized,
+                {
+                    let mut current = self.parent();
+                    while let ::std::option::Option::Some(parent) = current {
+                        if *parent == *other {
+                            return true;
+                        }
+                        current = parent.parent();
+                    }
+                    false
+                }
+            ] [$($impl_items)*]
+            $(#[$attr])* $vis $label_name $(; $($rest)*)?
+        );
+    };
+    (
+        @with [$($trait_items:tt)*] [$($impl_items:tt)*]
+        $(#[$attr:meta])* $vis:vis $label_name:ident; stable_hash $(; $($rest:tt)*)?
+    ) => {
+        $crate::define_label!(
+            @with [
+                $($trait_items)*
+
+                /// Returns a tag identifying the concrete type of this `
+                #[doc = stringify!($label_name)]
+                /// ` in serialized data.
+                ///
+                /// Defaults to the [`type_name`](std::any::type_name) of the type, which may change
+                /// when the type is moved or the compiler is updated. Override it with a fixed
+                /// string for labels whose serialized form must stay stable.
+                fn type_tag(&self) -> &'static str {
+                    ::std::any::type_name::<Self>()
+                }
+
+                /// Returns a hash of this `
+                #[doc = stringify!($label_name)]
+                /// ` which is stable across compilations, combining its
+                /// [`type_tag`](Self::type_tag) and value instead of its `TypeId`.
+                ///
+                /// The tag is written as its length followed by its bytes, independent of the
+                /// `Hash` implementation of `str`. The value is hashed with its `Hash`
+                /// implementation, whose byte stream the standard library does not guarantee to be
+                /// stable across compiler versions for its own types.
+                fn stable_hash(&self) -> u64 {
+                    let mut state = $crate::StableHasher::new();
+                    let tag = self.type_tag();
+                    ::std::hash::Hasher::write(&mut state, &(tag.len() as u64).to_le_bytes());
+                    ::std::hash::Hasher::write(&mut state, tag.as_bytes());
+                    self.as_dyn_hash().dyn_hash_value(&mut state);
+                    ::std::hash::Hasher::finish(&state)
+                }
+            ] [
+                $($impl_items)*
+
+                fn type_tag(&self) -> &'static str {
+                    (**self).type_tag()
+                }
+            ]
+            $(#[$attr])* $vis $label_name $(; $($rest)*)?
+        );
+    };
+    (
+        @with [$($trait_items:tt)*] [$($impl_items:tt)*]
+        $(#[$attr:meta])* $vis:vis $label_name:ident; interned $(; $($rest:tt)*)?
+    ) => {
+        $crate::define_label!(
+            @with [
+                $($trait_items)*
+
+                /// Returns the [`Interned`](labels::intern::Interned) value of the default value of
+                /// this type, which reads best for unit-struct labels.
+                #[must_use]
+                fn interned() -> $crate::intern::Interned<dyn $label_name>
+                where
+                    Self: ::std::default::Default + Sized,
+                {
+                    <Self as ::std::default::Default>::default().intern()
+                }
+            ] [$($impl_items)*]
+            $(#[$attr])* $vis $label_name $(; $($rest)*)?
+        );
+    };
+    (
+        @with [$($trait_items:tt)*] [$($impl_items:tt)*]
+        $(#[$attr:meta])* $vis:vis $label_name:ident $(;)? $(
         extra_methods:{ $($(#[$method_attr:meta])* fn $method:ident(&$self:ident) -> $ret:ty $($body:block)?)* }
         extra_methods_impl:{ $(fn $impl_method:ident(&$impl_self:ident) -> $impl_ret:ty $impl_body:block)* })?
     ) => {
@@ -73,14 +355,28 @@ macro_rules! define_label {
             fn as_dyn_hash(&self) -> &dyn $crate::DynHash;
 
             /// Returns an [`Interned`](labels::intern::Interned) value corresponding to `self`.
+            #[must_use]
             fn intern(&self) -> $crate::intern::Interned<dyn $label_name>
             where
                 Self: Sized,
             {
                 $crate::__paste::paste! {
-                    [<$label_name:upper _INTERNER>].intern(self)
+                    let interned = [<$label_name:upper _INTERNER>].intern(self);
+                    if let ::std::option::Option::Some(parent) = self.__label_parent() {
+                        [<$label_name:upper _PARENTS>].register(interned, parent);
+                    }
                 }
+                interned
             }
+
+            /// Returns the parent declared with the `#[labels::parent]` attribute, which is
+            /// registered when the label is first interned.
+            #[doc(hidden)]
+            fn __label_parent(&self) -> ::std::option::Option<$crate::intern::Interned<dyn $label_name>> {
+                ::std::option::Option::None
+            }
+
+            $($trait_items)*
         }
 
         impl $label_name for $crate::intern::Interned<dyn $label_name> {
@@ -103,6 +399,41 @@ macro_rules! define_label {
             fn intern(&self) -> Self {
                 *self
             }
+
+            $($impl_items)*
+        }
+
+        impl dyn $label_name {
+            /// Returns every interned `
+            #[doc = stringify!($label_name)]
+            /// ` in the order it was first interned.
+            $vis fn in_first_intern_order() -> ::std::vec::Vec<$crate::intern::Interned<dyn $label_name>> {
+                $crate::__paste::paste! {
+                    [<$label_name:upper _INTERNER>].to_vec()
+                }
+            }
+
+            /// Returns the [`Debug`](::std::fmt::Debug) representation of every interned `
+            #[doc = stringify!($label_name)]
+            /// ` in the order it was first interned, for example to suggest similar names in
+            /// error messages.
+            $vis fn known_names() -> ::std::vec::Vec<&'static str> {
+                static NAMES: $crate::intern::Interner<str> = $crate::intern::Interner::new();
+                <dyn $label_name>::in_first_intern_order()
+                    .into_iter()
+                    .map(|label| NAMES.intern_fmt(::std::format_args!("{label:?}")).0)
+                    .collect()
+            }
+
+            $crate::__paste::paste! {
+                $crate::__label_reset_hook!($vis [<$label_name:upper _INTERNER>]);
+            }
+        }
+
+        impl<T: $label_name> ::std::convert::From<T> for ::std::boxed::Box<dyn $label_name> {
+            fn from(value: T) -> Self {
+                ::std::boxed::Box::new(value)
+            }
         }
 
         impl PartialEq for dyn $label_name {
@@ -113,6 +444,18 @@ macro_rules! define_label {
 
         impl Eq for dyn $label_name {}
 
+        impl PartialEq<::std::boxed::Box<dyn $label_name>> for $crate::intern::Interned<dyn $label_name> {
+            fn eq(&self, other: &::std::boxed::Box<dyn $label_name>) -> bool {
+                **self == **other
+            }
+        }
+
+        impl PartialEq<$crate::intern::Interned<dyn $label_name>> for ::std::boxed::Box<dyn $label_name> {
+            fn eq(&self, other: &$crate::intern::Interned<dyn $label_name>) -> bool {
+                **self == **other
+            }
+        }
+
         impl ::std::hash::Hash for dyn $label_name {
             fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
                 self.as_dyn_hash().dyn_hash(state);
@@ -124,6 +467,10 @@ macro_rules! define_label {
                 Box::leak(self.dyn_clone())
             }
 
+            fn fmt_debug(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::fmt::Debug::fmt(self, f)
+            }
+
             fn ref_eq(&self, other: &Self) -> bool {
                 if self.as_dyn_eq().type_id() != other.as_dyn_eq().type_id() {
                     return false;
@@ -134,13 +481,175 @@ macro_rules! define_label {
             fn ref_hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
                 use ::std::hash::Hash;
                 self.as_dyn_eq().type_id().hash(state);
-                (self as *const Self as *const ()).hash(state);
+                state.write_u64($crate::intern::mix_address(self as *const Self as *const ()));
+            }
+
+            fn value_type_id(&self) -> ::std::any::TypeId {
+                self.as_dyn_eq().type_id()
+            }
+        }
+
+        impl $crate::intern::ToBoxed for dyn $label_name {
+            fn to_boxed(&self) -> ::std::boxed::Box<Self> {
+                self.dyn_clone()
             }
         }
 
         $crate::__paste::paste! {
-            static [<$label_name:upper _INTERNER>]: $crate::intern::Interner<dyn $label_name> =
-                $crate::intern::Interner::new();
+            $crate::__define_matches_label!(($) [<matches_ $label_name:snake>] $label_name);
         }
+
+        $crate::__paste::paste! {
+            /// Values which can be converted into a sequence of interned `
+            #[doc = stringify!($label_name)]
+            /// `s: a single label, or a tuple of up to twelve labels.
+            $vis trait [<Into $label_name s>] {
+                /// Interns the labels, preserving their order.
+                fn [<into_ $label_name:snake s>](
+                    self,
+                ) -> ::std::vec::IntoIter<$crate::intern::Interned<dyn $label_name>>;
+            }
+
+            impl<T: $label_name> [<Into $label_name s>] for T {
+                fn [<into_ $label_name:snake s>](
+                    self,
+                ) -> ::std::vec::IntoIter<$crate::intern::Interned<dyn $label_name>> {
+                    ::std::vec![$label_name::intern(&self)].into_iter()
+                }
+            }
+
+            $crate::__impl_into_labels!(
+                [<Into $label_name s>] [<into_ $label_name:snake s>] $label_name;
+                T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11
+            );
+        }
+
+        $crate::__paste::paste! {
+            $crate::__label_interner!([<$label_name:upper _INTERNER>] $label_name);
+
+            static [<$label_name:upper _PARENTS>]: $crate::intern::LabelParents<dyn $label_name> =
+                $crate::intern::LabelParents::new();
+        }
+    };
+    ($(#[$attr:meta])* $vis:vis $label_name:ident $($rest:tt)*) => {
+        $crate::define_label!(@with [] [] $(#[$attr])* $vis $label_name $($rest)*);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "dylib-registry"))]
+macro_rules! __label_interner {
+    ($name:ident $label_name:ident) => {
+        static $name: $crate::intern::Interner<dyn $label_name> = $crate::intern::Interner::new();
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "dylib-registry")]
+macro_rules! __label_interner {
+    ($name:ident $label_name:ident) => {
+        static $name: ::std::sync::LazyLock<&'static $crate::intern::Interner<dyn $label_name>> =
+            ::std::sync::LazyLock::new(|| {
+                $crate::intern::global_interner(::std::concat!(
+                    ::std::module_path!(),
+                    "::",
+                    ::std::stringify!($label_name)
+                ))
+            });
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "clear")]
+macro_rules! __label_reset_hook {
+    ($vis:vis $interner:ident) => {
+        /// Removes every interned label, so each test can start from a clean state, see
+        /// [`Interner::clear`](labels::intern::Interner::clear). Only available in tests.
+        #[cfg(test)]
+        #[allow(dead_code)]
+        $vis fn reset_interner_for_tests() {
+            $interner.clear();
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "clear"))]
+macro_rules! __label_reset_hook {
+    ($vis:vis $interner:ident) => {};
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[rustfmt::skip]
+macro_rules! __define_matches_label {
+    (($d:tt) $name:ident $label_name:ident) => {
+        /// Returns `true` if the given `
+        #[doc = stringify!($label_name)]
+        /// `, which may also be interned, is of the given concrete type.
+        #[allow(unused_macros)]
+        macro_rules! $name {
+            ($d value:expr, $d ty:ty $d(,)?) => {
+                $crate::DynEq::as_any($d value.as_dyn_eq()).is::<$d ty>()
+            };
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_into_labels {
+    ($trait:ident $method:ident $label_name:ident;) => {};
+    ($trait:ident $method:ident $label_name:ident; $first:ident $($rest:ident)*) => {
+        impl<$first: $label_name, $($rest: $label_name),*> $trait for ($first, $($rest,)*) {
+            #[allow(non_snake_case)]
+            fn $method(self) -> ::std::vec::IntoIter<$crate::intern::Interned<dyn $label_name>> {
+                let ($first, $($rest,)*) = self;
+                ::std::vec![
+                    $label_name::intern(&$first),
+                    $($label_name::intern(&$rest)),*
+                ]
+                .into_iter()
+            }
+        }
+
+        $crate::__impl_into_labels!($trait $method $label_name; $($rest)*);
+    };
+}
+
+/// Generates functions converting interned labels between two label traits which are
+/// implemented by the same concrete types.
+///
+/// `impl_label_bridge!(pub TraitA <-> TraitB: Foo, Bar)` generates `trait_a_to_trait_b` and
+/// `trait_b_to_trait_a`. They downcast a label to one of the listed types and intern it into the
+/// other trait's interner, returning `None` for labels of any other type.
+#[macro_export]
+macro_rules! impl_label_bridge {
+    (@convert $vis:vis $from:ident -> $to:ident: $($ty:ty),+) => {
+        $crate::__paste::paste! {
+            #[doc = concat!(
+                "Converts an interned `", stringify!($from), "` into an interned `",
+                stringify!($to), "`, if its type implements both traits."
+            )]
+            $vis fn [<$from:snake _to_ $to:snake>](
+                label: $crate::intern::Interned<dyn $from>,
+            ) -> ::std::option::Option<$crate::intern::Interned<dyn $to>> {
+                let any = $crate::DynEq::as_any($from::as_dyn_eq(&*label));
+                $(
+                    if let ::std::option::Option::Some(label) = any.downcast_ref::<$ty>() {
+                        return ::std::option::Option::Some(<$ty as $to>::intern(label));
+                    }
+                )+
+                ::std::option::Option::None
+            }
+        }
+    };
+    ($vis:vis $a:ident <-> $b:ident: $($ty:ty),+ $(,)?) => {
+        $crate::impl_label_bridge!(@convert $vis $a -> $b: $($ty),+);
+        $crate::impl_label_bridge!(@convert $vis $b -> $a: $($ty),+);
     };
 }
@@ -0,0 +1,93 @@
+use std::hash::BuildHasher;
+
+use crate::intern::{DefaultHashBuilder, InternError, Interner};
+
+/// A string interned by a [`SymbolTable`], identified by a dense 32-bit index.
+///
+/// Symbols are a quarter of the size of an [`Interned<str>`](crate::intern::Interned), which
+/// suits syntax trees and other structures holding many strings. Symbols of different tables
+/// must not be mixed up, since equal indices refer to different strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Returns the index of the symbol, which counts up from zero in the order strings were
+    /// first interned.
+    pub const fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+/// A string interner handing out [`Symbol`]s.
+pub struct SymbolTable<S = DefaultHashBuilder> {
+    strings: Interner<str, S>,
+}
+
+impl<S> Default for SymbolTable<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> SymbolTable<S> {
+    pub const fn new() -> Self {
+        Self {
+            strings: Interner::new(),
+        }
+    }
+}
+
+impl<S: BuildHasher + Default> SymbolTable<S> {
+    /// Returns the [`Symbol`] of `value`, leaking a copy of `value` if it was not interned
+    /// before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` was not interned before and cannot be interned, see
+    /// [`Interner::try_intern`], or if the table holds more than `u32::MAX` strings.
+    #[must_use]
+    pub fn intern(&self, value: &str) -> Symbol {
+        match self.try_intern(value) {
+            Ok(symbol) => symbol,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Like [`SymbolTable::intern`], but returns an error instead of panicking if `value` cannot
+    /// be interned, see [`Interner::try_intern`].
+    pub fn try_intern(&self, value: &str) -> Result<Symbol, InternError> {
+        self.strings
+            .try_intern_indexed(value)
+            .map(|(_, index)| Symbol(index))
+    }
+
+    /// Returns the [`Symbol`] of `value` if it was interned before, without interning it
+    /// otherwise.
+    pub fn get(&self, value: &str) -> Option<Symbol> {
+        self.strings
+            .get_indexed(value)
+            .map(|(_, index)| Symbol(index))
+    }
+
+    /// Returns the string of `symbol`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `symbol` was not interned by this table.
+    pub fn resolve(&self, symbol: Symbol) -> &'static str {
+        self.strings
+            .from_index(symbol.0)
+            .expect("symbol was not interned by this table")
+            .0
+    }
+
+    /// Returns the number of distinct strings interned.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Returns `true` if no string has been interned.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
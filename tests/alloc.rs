@@ -0,0 +1,81 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use labels::intern::Interner;
+
+/// The size of the buffers interned by the tests, larger than any allocation of the interner
+/// itself, so only copies of the buffers are counted.
+const BUFFER_SIZE: usize = 64 * 1024;
+
+thread_local! {
+    static BUFFER_ALLOCS: Cell<usize> = const { Cell::new(0) };
+    static BUFFER_DEALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts the allocations and deallocations of buffers of [`BUFFER_SIZE`] made by each thread.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() == BUFFER_SIZE {
+            BUFFER_ALLOCS.set(BUFFER_ALLOCS.get() + 1);
+        }
+        // SAFETY: forwarded from the caller.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if layout.size() == BUFFER_SIZE {
+            BUFFER_DEALLOCS.set(BUFFER_DEALLOCS.get() + 1);
+        }
+        // SAFETY: forwarded from the caller.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the buffers of [`BUFFER_SIZE`] allocated and deallocated on this thread by `f`.
+fn count_buffers<R>(f: impl FnOnce() -> R) -> (R, usize, usize) {
+    let (allocs, deallocs) = (BUFFER_ALLOCS.get(), BUFFER_DEALLOCS.get());
+    let result = f();
+    (
+        result,
+        BUFFER_ALLOCS.get() - allocs,
+        BUFFER_DEALLOCS.get() - deallocs,
+    )
+}
+
+fn buffer(seed: u64) -> Vec<u64> {
+    let values: Vec<u64> = (seed..).take(BUFFER_SIZE / 8).collect();
+    assert_eq!(values.capacity(), values.len());
+    values
+}
+
+#[test]
+fn intern_vec_reuses_the_buffer_on_a_miss() {
+    let interner: Interner<[u64]> = Interner::new();
+    let values = buffer(0);
+    let ptr = values.as_ptr();
+
+    let (interned, allocs, deallocs) = count_buffers(|| interner.intern_vec(values));
+    assert_eq!((allocs, deallocs), (0, 0));
+    assert_eq!(interned.as_ptr().cast::<u64>(), ptr);
+    assert!(interned.iter().copied().eq(0..BUFFER_SIZE as u64 / 8));
+}
+
+#[test]
+fn intern_vec_drops_the_buffer_on_a_hit() {
+    let interner: Interner<[u64]> = Interner::new();
+    let first = interner.intern_vec(buffer(1));
+
+    let again = buffer(1);
+    let (interned, allocs, deallocs) = count_buffers(|| interner.intern_vec(again));
+    assert_eq!((allocs, deallocs), (0, 1));
+    assert!(interned.ptr_eq(&first));
+    assert_eq!(interner.len(), 1);
+
+    let (_, allocs, _) = count_buffers(|| interner.intern(&buffer(1)));
+    assert_eq!(allocs, 1, "only the argument buffer is allocated on a hit");
+}
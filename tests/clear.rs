@@ -0,0 +1,110 @@
+#![cfg(feature = "clear")]
+
+use labels::define_label;
+use labels::intern::Interner;
+
+define_label!(ResetLabel);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct A;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct B;
+
+macro_rules! impl_reset_label {
+    ($($ty:ty),*) => {$(
+        impl ResetLabel for $ty {
+            fn dyn_clone(&self) -> Box<dyn ResetLabel> {
+                Box::new(self.clone())
+            }
+
+            fn as_dyn_eq(&self) -> &dyn labels::DynEq {
+                self
+            }
+
+            fn as_dyn_hash(&self) -> &dyn labels::DynHash {
+                self
+            }
+        }
+    )*};
+}
+
+impl_reset_label!(A, B);
+
+#[test]
+fn reset_label_interner() {
+    let a = A.intern();
+    let b = B.intern();
+    assert_eq!(<dyn ResetLabel>::in_first_intern_order(), [a, b]);
+
+    <dyn ResetLabel>::reset_interner_for_tests();
+    assert!(<dyn ResetLabel>::in_first_intern_order().is_empty());
+
+    let b2 = B.intern();
+    let a2 = A.intern();
+    assert_eq!(<dyn ResetLabel>::in_first_intern_order(), [b2, a2]);
+    assert_eq!(a2, A.intern());
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn clear_forgets_provenance() {
+    let interner: Interner<str> = Interner::new();
+    let kept = interner.intern("kept");
+    let snapshot = interner.snapshot();
+    let removed = interner.intern("removed");
+    interner.restore(&snapshot);
+    assert_eq!(removed.provenance(), None);
+    assert_eq!(kept.provenance(), Some(interner.id()));
+
+    interner.clear();
+    assert_eq!(kept.provenance(), None);
+}
+
+#[test]
+fn clear_empties_the_interner() {
+    let interner: Interner<str> = Interner::new();
+    let old = interner.intern("value");
+    // Finding the value again publishes a snapshot, which must not outlive the clear.
+    let _ = interner.intern("value");
+    let _ = interner.intern("value");
+    interner.clear();
+
+    assert!(interner.is_empty());
+    assert_eq!(interner.get("value"), None);
+    assert_eq!(interner.stats().hits, 0);
+    assert_eq!(&*old, "value");
+
+    let new = interner.intern("value");
+    assert!(!new.ptr_eq(&old));
+    assert_eq!(interner.len(), 1);
+    assert!(interner.intern("value").ptr_eq(&new));
+}
+
+#[test]
+fn restore_rolls_back_to_a_snapshot() {
+    let interner: Interner<str> = Interner::new();
+    let kept = interner.intern("kept");
+    let snapshot = interner.snapshot();
+    let removed = interner.intern("removed");
+    let _ = interner.intern("removed");
+    interner.restore(&snapshot);
+
+    assert_eq!(interner.len(), 1);
+    assert!(interner.diff(&snapshot).is_empty());
+    assert_eq!(interner.get("removed"), None);
+    assert!(interner.intern("kept").ptr_eq(&kept));
+    assert!(!interner.intern("removed").ptr_eq(&removed));
+    assert_eq!(&*removed, "removed");
+}
+
+#[test]
+#[should_panic = "snapshot does not match"]
+fn restore_rejects_snapshots_of_removed_values() {
+    let interner: Interner<str> = Interner::new();
+    let _ = interner.intern("a");
+    let snapshot = interner.snapshot();
+    interner.clear();
+    let _ = interner.intern("b");
+    interner.restore(&snapshot);
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "derive")]
+
+use labels::intern::{Internable, Interner};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Internable)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Internable)]
+struct Wrapper<T> {
+    inner: T,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Internable)]
+enum Shape {
+    Circle(u32),
+    Square { side: u32 },
+    Empty,
+}
+
+#[test]
+fn derive_struct() {
+    let interner = Interner::<Point>::new();
+    let a = interner.intern(&Point { x: 1, y: 2 });
+    let b = interner.intern(&Point { x: 1, y: 2 });
+    let c = interner.intern(&Point { x: 2, y: 1 });
+    assert!(a.ptr_eq(&b));
+    assert!(!a.ptr_eq(&c));
+    assert_eq!(*a, Point { x: 1, y: 2 });
+    assert_eq!(interner.len(), 2);
+}
+
+#[test]
+fn derive_generic_struct() {
+    let interner = Interner::<Wrapper<String>>::new();
+    let a = interner.intern(&Wrapper {
+        inner: "a".to_owned(),
+    });
+    let b = interner.intern(&Wrapper {
+        inner: "a".to_owned(),
+    });
+    let c = interner.intern(&Wrapper {
+        inner: "b".to_owned(),
+    });
+    assert!(a.ptr_eq(&b));
+    assert!(!a.ptr_eq(&c));
+    assert_eq!(interner.len(), 2);
+}
+
+#[test]
+fn derive_enum() {
+    let interner = Interner::<Shape>::new();
+    let shapes = [
+        Shape::Circle(1),
+        Shape::Square { side: 1 },
+        Shape::Empty,
+        Shape::Circle(1),
+        Shape::Empty,
+    ];
+    let interned: Vec<_> = shapes.iter().map(|shape| interner.intern(shape)).collect();
+    assert!(interned[0].ptr_eq(&interned[3]));
+    assert!(interned[2].ptr_eq(&interned[4]));
+    assert!(!interned[0].ptr_eq(&interned[1]));
+    assert_eq!(interner.len(), 3);
+}
@@ -0,0 +1,229 @@
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use labels::define_label;
+use labels::intern::{ErasedInterned, Interned, Interner};
+
+define_label!(Label);
+
+macro_rules! impl_label {
+    ($($ty:ident),*) => {
+        $(
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            struct $ty;
+
+            impl Label for $ty {
+                fn dyn_clone(&self) -> Box<dyn Label> {
+                    Box::new(self.clone())
+                }
+
+                fn as_dyn_eq(&self) -> &dyn labels::DynEq {
+                    self
+                }
+
+                fn as_dyn_hash(&self) -> &dyn labels::DynHash {
+                    self
+                }
+            }
+        )*
+    };
+}
+
+impl_label!(First, Second);
+
+fn to_path_buf(path: impl AsRef<Path>) -> PathBuf {
+    path.as_ref().to_path_buf()
+}
+
+fn to_os_str(value: &impl AsRef<OsStr>) -> &OsStr {
+    value.as_ref()
+}
+
+#[test]
+fn interned_paths_in_fs_apis() {
+    let paths: Interner<Path> = Interner::new();
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    let path: Interned<Path> = paths.intern(&manifest);
+
+    assert_eq!(to_path_buf(path), manifest);
+    assert!(std::fs::metadata(path).unwrap().is_file());
+    assert_eq!(to_os_str(&path), manifest.as_os_str());
+
+    let os_strs: Interner<OsStr> = Interner::new();
+    let os_str = os_strs.intern(manifest.as_os_str());
+    assert_eq!(to_path_buf(os_str), manifest);
+    assert_eq!(to_os_str(&os_str), manifest.as_os_str());
+
+    let strs: Interner<str> = Interner::new();
+    let str = strs.intern(manifest.to_str().unwrap());
+    assert_eq!(to_path_buf(str), manifest);
+}
+
+#[test]
+fn index_map_keys_keep_insertion_order() {
+    let interner: Interner<str> = Interner::new();
+    let mut map = indexmap::IndexMap::new();
+    for (i, key) in ["zeta", "alpha", "mu", "beta"].into_iter().enumerate() {
+        map.insert(interner.intern(key), i);
+    }
+    map.insert(interner.intern("alpha"), 10);
+
+    let keys: Vec<&str> = map.keys().map(|key| key.0).collect();
+    assert_eq!(keys, ["zeta", "alpha", "mu", "beta"]);
+    assert_eq!(map[&interner.intern("alpha")], 10);
+
+    map.sort_keys();
+    let keys: Vec<&str> = map.keys().map(|key| key.0).collect();
+    assert_eq!(keys, ["alpha", "beta", "mu", "zeta"]);
+}
+
+/// A [`Hasher`] which returns the last integer written, like hashers doing no mixing of their own.
+#[derive(Default)]
+struct IdentityHasher(u64);
+
+impl Hasher for IdentityHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, _: &[u8]) {
+        unreachable!("only integers are written");
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0 = value;
+    }
+}
+
+/// Returns the number of distinct buckets out of `BUCKETS` the hashes fall into.
+fn occupied_buckets(hashes: impl Iterator<Item = u64>) -> usize {
+    const BUCKETS: u64 = 1024;
+    hashes
+        .map(|hash| hash % BUCKETS)
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+#[test]
+fn mixed_addresses_spread_over_buckets() {
+    let interner: Interner<u64> = Interner::new();
+    let keys: Vec<Interned<u64>> = (0..4096).map(|i| interner.intern(&i)).collect();
+
+    let raw = occupied_buckets(keys.iter().map(|key| key.as_ptr().addr() as u64));
+    let mixed = occupied_buckets(keys.iter().map(|key| {
+        let mut hasher = IdentityHasher::default();
+        key.hash(&mut hasher);
+        hasher.finish()
+    }));
+    // 4096 keys spread uniformly leave about 1024 * e^-4 ≈ 19 buckets empty.
+    assert!(raw <= 1024 / 8, "aligned addresses use {raw} buckets");
+    assert!(mixed >= 980, "mixed addresses use {mixed} buckets");
+
+    let map: HashMap<Interned<u64>, usize, BuildHasherDefault<IdentityHasher>> =
+        keys.iter().copied().zip(0..).collect();
+    assert!(keys.iter().zip(0..).all(|(key, i)| map[key] == i));
+}
+
+#[test]
+fn debug_truncated_elides_long_strings() {
+    let interner: Interner<str> = Interner::new();
+    let long = interner.intern(&"abc".repeat(1000));
+    assert_eq!(
+        format!("{:?}", long.debug_truncated(5)),
+        r#""abcab"... (3000 bytes)"#
+    );
+
+    let short = interner.intern("short");
+    assert_eq!(format!("{:?}", short.debug_truncated(5)), r#""short""#);
+
+    // The prefix is cut at a character boundary.
+    let wide = interner.intern("ééé");
+    assert_eq!(
+        format!("{:?}", wide.debug_truncated(3)),
+        r#""é"... (6 bytes)"#
+    );
+}
+
+#[test]
+fn erased_interned_downcasts_to_its_own_type() {
+    let strs: Interner<str> = Interner::new();
+    let paths: Interner<Path> = Interner::new();
+    let numbers: Interner<u64> = Interner::new();
+
+    let str = strs.intern("a");
+    let path = paths.intern(Path::new("a"));
+    let number = numbers.intern(&7);
+    let erased = [
+        ErasedInterned::from(str),
+        ErasedInterned::from(path),
+        ErasedInterned::from(number),
+    ];
+
+    assert!(erased[0].is::<str>());
+    assert_eq!(erased[0].downcast::<str>(), Some(str));
+    assert_eq!(erased[1].downcast::<Path>(), Some(path));
+    assert_eq!(erased[2].downcast::<u64>(), Some(number));
+    assert_eq!(erased[0].as_ptr(), str.as_ptr().cast());
+
+    assert!(!erased[0].is::<Path>());
+    assert_eq!(erased[0].downcast::<Path>(), None);
+    assert_eq!(erased[1].downcast::<str>(), None);
+    assert_eq!(erased[2].downcast::<u32>(), None);
+}
+
+#[test]
+fn erased_interned_equality_needs_the_same_type_and_address() {
+    let strs: Interner<str> = Interner::new();
+    let bytes: Interner<[u8]> = Interner::new();
+
+    let a = strs.intern("a");
+    assert_eq!(
+        ErasedInterned::new(a),
+        ErasedInterned::new(strs.intern("a"))
+    );
+    assert_ne!(
+        ErasedInterned::new(a),
+        ErasedInterned::new(strs.intern("b"))
+    );
+
+    // Equal contents of another type are a different value once erased.
+    assert_ne!(
+        ErasedInterned::new(a),
+        ErasedInterned::new(bytes.intern(b"a"))
+    );
+
+    // Empty values are equal wherever they are.
+    let empty = strs.intern("");
+    let projected = a.map(|a| &a[1..]);
+    assert!(!empty.ptr_eq(&projected));
+    assert_eq!(ErasedInterned::new(empty), ErasedInterned::new(projected));
+    let set: HashSet<_> = [empty, projected]
+        .into_iter()
+        .map(ErasedInterned::new)
+        .collect();
+    assert_eq!(set.len(), 1);
+
+    let set: HashSet<_> = [a, a, strs.intern("b")]
+        .into_iter()
+        .map(ErasedInterned::new)
+        .collect();
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn erased_unit_labels_of_different_types_are_different() {
+    let first = First.intern();
+    let second = Second.intern();
+    // Both are zero-sized, so they may share an address.
+    assert_ne!(first, second);
+
+    let (first, second) = (ErasedInterned::new(first), ErasedInterned::new(second));
+    assert_ne!(first, second);
+    assert_eq!(first, ErasedInterned::new(First.intern()));
+    assert_eq!(first.downcast::<dyn Label>(), Some(First.intern()));
+
+    let set: HashSet<_> = [first, second, first].into_iter().collect();
+    assert_eq!(set.len(), 2);
+}
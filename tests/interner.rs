@@ -0,0 +1,518 @@
+use std::sync::Arc;
+
+use labels::intern::{InternError, Interned, Interner, ReserveError};
+
+#[test]
+fn short_and_long_strings() {
+    let interner: Interner<str> = Interner::new();
+    let short = ["a", "bc", "twenty-two bytes long!"];
+    let long = ["x".repeat(5000), "y".repeat(100_000)];
+
+    let interned: Vec<Interned<str>> = short
+        .iter()
+        .copied()
+        .chain(long.iter().map(String::as_str))
+        .map(|value| interner.intern(value))
+        .collect();
+    for (value, interned) in short
+        .iter()
+        .copied()
+        .chain(long.iter().map(String::as_str))
+        .zip(&interned)
+    {
+        assert_eq!(&**interned, value);
+        let again = interner.intern(value);
+        assert!(again.ptr_eq(interned));
+        assert_ne!(again.as_ptr(), value as *const str);
+    }
+    assert_eq!(interner.len(), short.len() + long.len());
+}
+
+#[derive(Clone, Debug)]
+struct Keyed {
+    key: u32,
+    detail: Option<&'static str>,
+}
+
+impl PartialEq for Keyed {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Keyed {}
+
+impl std::hash::Hash for Keyed {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+impl labels::intern::InternByClone for Keyed {}
+
+#[test]
+fn upgrade_replaces_canonical_value() {
+    let interner: Interner<Keyed> = Interner::new();
+    let key = Keyed {
+        key: 1,
+        detail: None,
+    };
+    let old = interner.intern(&key);
+    let upgraded = interner.upgrade(
+        &key,
+        Box::new(Keyed {
+            key: 1,
+            detail: Some("rich"),
+        }),
+    );
+
+    assert_eq!(interner.get(&key).unwrap().detail, Some("rich"));
+    assert_eq!(interner.intern(&key), upgraded);
+    assert_eq!(old.detail, None);
+    assert_ne!(old, upgraded);
+    assert_eq!(interner.len(), 1);
+}
+
+#[test]
+fn sealed_interner_rejects_new_values() {
+    let interner: Interner<str> = Interner::new();
+    let existing = interner.intern("existing");
+    interner.seal();
+
+    assert!(interner.is_sealed());
+    assert_eq!(interner.try_intern("new"), Err(InternError::Sealed));
+    assert_eq!(interner.try_intern("existing"), Ok(existing));
+    assert_eq!(interner.intern("existing"), existing);
+    assert_eq!(interner.get("new"), None);
+    assert_eq!(interner.len(), 1);
+}
+
+#[test]
+#[should_panic = "sealed"]
+fn sealed_interner_panics_on_intern() {
+    let interner: Interner<str> = Interner::new();
+    interner.seal();
+    let _ = interner.intern("new");
+}
+
+#[test]
+fn try_reserve() {
+    let interner: Interner<str> = Interner::with_capacity(2);
+    assert_eq!(interner.try_reserve(100), Ok(()));
+    assert!(interner.stats().capacity >= 100);
+
+    let _ = interner.intern("value");
+    assert_eq!(
+        interner.try_reserve(usize::MAX),
+        Err(ReserveError::CapacityOverflow)
+    );
+    assert_eq!(interner.len(), 1);
+}
+
+#[test]
+fn write_dump() {
+    let interner: Interner<str> = Interner::new();
+    for value in ["first", "two\nlines", "back\\slash\r", ""] {
+        let _ = interner.intern(value);
+    }
+    let mut out = Vec::new();
+    interner.write_dump(&mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(
+        out.lines().collect::<Vec<_>>(),
+        ["first", "two\\nlines", "back\\\\slash\\r", ""]
+    );
+}
+
+#[test]
+fn intern_preferring_adopts_authoritative_values() {
+    let authoritative: Interner<str> = Interner::new();
+    let local: Interner<str> = Interner::new();
+    let canonical = authoritative.intern("shared");
+    let own = local.intern("own");
+
+    let adopted = local.intern_preferring("shared", &authoritative);
+    assert!(adopted.ptr_eq(&canonical));
+    assert!(local.intern("shared").ptr_eq(&canonical));
+
+    let fresh = local.intern_preferring("fresh", &authoritative);
+    assert_eq!(&*fresh, "fresh");
+    assert!(!authoritative.contains("fresh"));
+    assert!(local.intern_preferring("own", &authoritative).ptr_eq(&own));
+}
+
+thread_local! {
+    static HASHES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// A [`BuildHasher`](std::hash::BuildHasher) counting the hashes computed on the current thread.
+#[derive(Default)]
+struct CountingState(std::hash::RandomState);
+
+struct CountingHasher(std::hash::DefaultHasher);
+
+impl std::hash::BuildHasher for CountingState {
+    type Hasher = CountingHasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        CountingHasher(self.0.build_hasher())
+    }
+}
+
+impl std::hash::Hasher for CountingHasher {
+    fn finish(&self) -> u64 {
+        HASHES.set(HASHES.get() + 1);
+        self.0.finish()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.write(bytes);
+    }
+}
+
+fn count_hashes<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = HASHES.get();
+    let result = f();
+    (result, HASHES.get() - before)
+}
+
+#[test]
+fn values_are_hashed_once_per_intern() {
+    let interner: Interner<str, CountingState> = Interner::with_capacity(16);
+    let (_, hashes) = count_hashes(|| interner.intern("a"));
+    assert_eq!(hashes, 1);
+    // Finding the value under the lock publishes a snapshot.
+    let _ = interner.intern("a");
+
+    let (_, hashes) = count_hashes(|| interner.intern("b"));
+    assert_eq!(hashes, 1);
+    let (_, hashes) = count_hashes(|| interner.intern_string("c".to_owned()));
+    assert_eq!(hashes, 1);
+    let (found, hashes) = count_hashes(|| interner.get("d"));
+    assert_eq!((found, hashes), (None, 1));
+}
+
+#[test]
+fn values_snapshot_is_unaffected_by_concurrent_interning() {
+    let interner: Interner<str> = Interner::new();
+    let before: Vec<Interned<str>> = (0..100)
+        .map(|i| interner.intern(&format!("before {i}")))
+        .collect();
+    let snapshot = interner.values_snapshot();
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            for i in 0..1000 {
+                let _ = interner.intern(&format!("after {i}"));
+            }
+        });
+        for _ in 0..10 {
+            assert!(snapshot.iter().eq(before.iter().copied()));
+        }
+    });
+
+    assert_eq!(snapshot.len(), 100);
+    let fresh = interner.values_snapshot();
+    assert_eq!(fresh.len(), 1100);
+    assert!(fresh.iter().take(100).eq(before.iter().copied()));
+    assert_eq!(&*fresh.iter().last().unwrap(), "after 999");
+    assert!(Arc::ptr_eq(&fresh, &interner.values_snapshot()));
+}
+
+#[test]
+fn intern_if_denies_past_threshold() {
+    let interner: Interner<u32> = Interner::new();
+    let below = |stats: &labels::intern::InternStats| stats.entries < 3;
+    let first: Vec<Interned<u32>> = (0..3)
+        .map(|i| interner.intern_if(&i, below).unwrap())
+        .collect();
+
+    assert_eq!(interner.intern_if(&3, below), None);
+    assert!(!interner.contains(&3));
+    for (i, first) in (0..3).zip(&first) {
+        assert!(interner.intern_if(&i, |_| false).unwrap().ptr_eq(first));
+    }
+    assert_eq!(interner.len(), 3);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn provenance_of_two_interners() {
+    let first: Interner<str> = Interner::new();
+    let second: Interner<str> = Interner::new();
+    let a = first.intern("value");
+    let b = second.intern("value");
+
+    assert_eq!(a.provenance(), Some(first.id()));
+    assert_eq!(b.provenance(), Some(second.id()));
+    assert_ne!(a.provenance(), b.provenance());
+    // Adopting a value of another interner keeps its provenance.
+    let adopted = first.intern("adopted");
+    assert_eq!(second.intern_ref(adopted.0).provenance(), Some(first.id()));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn provenance_follows_upgrades() {
+    let interner: Interner<Keyed> = Interner::new();
+    let key = Keyed {
+        key: 2,
+        detail: None,
+    };
+    let old = interner.intern(&key);
+    let upgraded = interner.upgrade(
+        &key,
+        Box::new(Keyed {
+            key: 2,
+            detail: Some("rich"),
+        }),
+    );
+    assert_eq!(upgraded.provenance(), Some(interner.id()));
+    assert_eq!(old.provenance(), None);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn provenance_is_kept_per_type() {
+    let strings: Interner<str> = Interner::new();
+    let bytes: Interner<[u8]> = Interner::new();
+    let string = strings.intern("same address");
+    let slice = bytes.intern_ref(string.0.as_bytes());
+
+    assert_eq!(slice.as_ptr().cast::<u8>(), string.as_ptr().cast::<u8>());
+    assert_eq!(string.provenance(), Some(strings.id()));
+    assert_eq!(slice.provenance(), Some(bytes.id()));
+}
+
+#[test]
+fn freeze_perfect_finds_every_value() {
+    let interner: Interner<str> = Interner::new();
+    let values: Vec<Interned<str>> = (0..5_000)
+        .map(|i| interner.intern(&format!("value_{i}")))
+        .collect();
+    interner.freeze_perfect();
+
+    assert!(interner.is_frozen() && interner.is_sealed());
+    for (i, value) in values.iter().enumerate() {
+        let key = format!("value_{i}");
+        assert!(interner.get(&key).unwrap().ptr_eq(value));
+        assert!(interner.intern(&key).ptr_eq(value));
+    }
+    assert_eq!(interner.get("value_5000"), None);
+    assert_eq!(interner.try_intern("value_5000"), Err(InternError::Sealed));
+    assert_eq!(interner.len(), values.len());
+}
+
+/// A value whose hash ignores its contents, so no perfect hash table separates two of them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Opaque(u32);
+
+impl std::hash::Hash for Opaque {
+    fn hash<H: std::hash::Hasher>(&self, _: &mut H) {}
+}
+
+impl labels::intern::InternByClone for Opaque {}
+
+#[test]
+fn freeze_perfect_falls_back_for_values_hashing_alike() {
+    let interner: Interner<Opaque> = Interner::new();
+    let values: Vec<Interned<Opaque>> = (0..100).map(|i| interner.intern(&Opaque(i))).collect();
+    interner.freeze_perfect();
+
+    assert!(interner.is_frozen());
+    for (i, value) in (0..).zip(&values) {
+        assert!(interner.get(&Opaque(i)).unwrap().ptr_eq(value));
+    }
+    assert_eq!(interner.get(&Opaque(100)), None);
+}
+
+#[test]
+fn content_hash_is_independent_of_the_interner() {
+    let first: Interner<str> = Interner::new();
+    let second: Interner<str> = Interner::new();
+    let (a, hash) = first.intern_hashed("content");
+    let (b, other_hash) = second.intern_hashed("content");
+
+    assert!(!a.ptr_eq(&b));
+    assert_eq!(hash, other_hash);
+    assert_eq!(first.intern_hashed("content").1, hash);
+    assert_ne!(first.intern_hashed("other").1, hash);
+
+    let mut state = labels::StableHasher::new();
+    std::hash::Hash::hash("content", &mut state);
+    assert_eq!(hash, std::hash::Hasher::finish(&state));
+}
+
+#[test]
+#[cfg(feature = "fast-hash")]
+fn fx_interner() {
+    let interner: labels::intern::FxInterner<str> = labels::intern::FxInterner::new();
+    let long = "x".repeat(10_000);
+    let value = interner.intern(&long);
+    assert!(interner.intern(&long).ptr_eq(&value));
+    assert_eq!(interner.get("other"), None);
+    assert_eq!(interner.len(), 1);
+}
+
+#[test]
+#[cfg(feature = "fast-hash")]
+fn default_interner_hashes_with_fx() {
+    use std::hash::BuildHasher;
+
+    use labels::intern::DefaultHashBuilder;
+
+    let state = DefaultHashBuilder::default();
+    assert_eq!(
+        state.hash_one("value"),
+        rustc_hash::FxBuildHasher.hash_one("value")
+    );
+
+    // An interner without a hasher parameter hashes with the same builder.
+    let interner: Interner<str> = Interner::<str, DefaultHashBuilder>::new();
+    let value = interner.intern("value");
+    assert_eq!(interner.get("value"), Some(value));
+}
+
+#[test]
+fn len_counts_distinct_values() {
+    let interner: Interner<str> = Interner::new();
+    assert!(interner.is_empty());
+    assert_eq!(interner.len(), 0);
+
+    for value in ["a", "b", "a", "c", "b"] {
+        let _ = interner.intern(value);
+    }
+    assert_eq!(interner.len(), 3);
+    assert!(!interner.is_empty());
+    let _ = interner.get("d");
+    assert_eq!(interner.len(), 3);
+}
+
+/// Returns the hits and misses counted by `interner` so far.
+fn lookups<T: labels::intern::Internable + ?Sized>(interner: &Interner<T>) -> (usize, usize) {
+    let stats = interner.stats();
+    (stats.hits, stats.misses)
+}
+
+#[test]
+fn every_lookup_is_counted() {
+    let interner: Interner<str> = Interner::new();
+    let _ = interner.intern_iter(["a", "b", "a"]);
+    assert_eq!(lookups(&interner), (1, 2));
+
+    let _ = interner.intern_with("c", || "c".into());
+    let _ = interner.intern_with("c", || unreachable!());
+    assert_eq!(lookups(&interner), (2, 3));
+
+    assert_eq!(interner.first_intern_index("b"), Some(1));
+    assert_eq!(interner.first_intern_index("d"), None);
+    assert_eq!(lookups(&interner), (3, 4));
+
+    let _ = interner.upgrade("a", "a".into());
+    let _ = interner.upgrade("e", "e".into());
+    assert_eq!(lookups(&interner), (4, 5));
+}
+
+#[test]
+fn diff_lists_values_interned_since_a_snapshot() {
+    let interner: Interner<str> = Interner::new();
+    let _ = interner.intern("before");
+    let snapshot = interner.snapshot();
+    assert!(interner.diff(&snapshot).is_empty());
+
+    let after = [interner.intern("x"), interner.intern("y")];
+    let _ = interner.intern("before");
+    assert_eq!(interner.diff(&snapshot), after);
+}
+
+#[test]
+#[should_panic = "snapshot does not match"]
+fn diff_rejects_snapshots_of_other_interners() {
+    let first: Interner<str> = Interner::new();
+    let second: Interner<str> = Interner::new();
+    let snapshot = first.snapshot();
+    let _ = second.diff(&snapshot);
+}
+
+#[test]
+fn try_intern_owned_reports_errors() {
+    let interner: Interner<str> = Interner::new();
+    let existing = interner.try_intern_owned("existing".into()).unwrap();
+    interner.seal();
+
+    assert_eq!(
+        interner.try_intern_owned("new".into()),
+        Err(InternError::Sealed)
+    );
+    assert_eq!(interner.try_intern_owned("existing".into()), Ok(existing));
+}
+
+#[test]
+fn intern_indexed_returns_the_index() {
+    let interner: Interner<str> = Interner::new();
+    let (a, a_index) = interner.intern_indexed("a");
+    let (b, b_index) = interner.intern_indexed("b");
+    assert_eq!((a_index, b_index), (0, 1));
+    assert_eq!(interner.intern_indexed("a"), (a, 0));
+    assert_eq!(interner.index(b), Some(b_index));
+    assert_eq!(interner.from_index(a_index), Some(a));
+    assert_eq!(lookups(&interner), (1, 2));
+
+    interner.seal();
+    assert_eq!(interner.try_intern_indexed("c"), Err(InternError::Sealed));
+    assert_eq!(interner.try_intern_indexed("b"), Ok((b, 1)));
+}
+
+#[test]
+fn get_indexed_returns_the_index() {
+    let interner: Interner<str> = Interner::new();
+    assert_eq!(interner.get_indexed("a"), None);
+    let (b, b_index) = interner.intern_indexed("b");
+    assert_eq!(interner.get_indexed("b"), Some((b, b_index)));
+    assert_eq!(interner.get_indexed("a"), None);
+    assert_eq!(lookups(&interner), (1, 3));
+}
+
+#[test]
+fn owned_std_types_are_internable() {
+    let strings: Interner<[String]> = Interner::new();
+    let words = vec!["a".to_owned(), "b".to_owned()];
+    let a = strings.intern(&words);
+    assert_eq!(strings.intern(&words), a);
+    assert_ne!(strings.intern(&words[..1]), a);
+    assert_eq!(&*a, &words[..]);
+
+    let options: Interner<Option<Vec<u32>>> = Interner::new();
+    let some = options.intern(&Some(vec![1, 2]));
+    assert_eq!(options.intern(&Some(vec![1, 2])), some);
+    assert_ne!(options.intern(&None), some);
+
+    let units: Interner<()> = Interner::new();
+    assert_eq!(units.intern(&()), units.intern(&()));
+    assert_eq!(units.len(), 1);
+}
+
+#[test]
+fn intern_fmt_formats_long_strings_once() {
+    use std::cell::Cell;
+    use std::fmt;
+
+    struct Counted<'a>(&'a Cell<usize>);
+
+    impl fmt::Display for Counted<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.set(self.0.get() + 1);
+            f.write_str(&"x".repeat(300))
+        }
+    }
+
+    let interner = Interner::<str>::new();
+    let calls = Cell::new(0);
+    let long = interner.intern_fmt(format_args!("a{}b", Counted(&calls)));
+    assert_eq!(calls.get(), 1);
+    assert_eq!(long.len(), 302);
+    assert_eq!(long, interner.intern(&format!("a{}b", "x".repeat(300))));
+
+    let short = interner.intern_fmt(format_args!("node_{}", 7));
+    assert_eq!(&*short, "node_7");
+}
@@ -0,0 +1,23 @@
+#![cfg(feature = "kstring")]
+
+use kstring::{KString, KStringCow, KStringRef};
+use labels::intern::Interner;
+
+#[test]
+fn round_trip_through_kstring() {
+    let interner: Interner<str> = Interner::new();
+    let interned = interner.intern("a string longer than the inline capacity of KString");
+
+    let owned = KString::from(interned);
+    assert_eq!(owned.as_str(), &*interned);
+    assert_eq!(owned.as_str().as_ptr(), interned.as_ptr().cast());
+    assert_eq!(interner.intern(&owned), interned);
+
+    let borrowed = KStringRef::from(interned);
+    assert_eq!(borrowed.as_str().as_ptr(), interned.as_ptr().cast());
+    assert_eq!(interner.intern(&borrowed), interned);
+
+    let cow = KStringCow::from(interned);
+    assert_eq!(cow.as_str().as_ptr(), interned.as_ptr().cast());
+    assert_eq!(interner.intern(&cow), interned);
+}
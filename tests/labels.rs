@@ -0,0 +1,390 @@
+use std::any::TypeId;
+
+use labels::intern::{InternError, Interned, Interner};
+use labels::{define_label, Ignored};
+
+define_label!(TestLabel; hierarchy);
+
+/// Implements a label trait for a type, with the given attributes and extra methods.
+macro_rules! impl_label {
+    ($(#[$attr:meta])* $label:ident for $ty:ty $(, $($method:tt)*)?) => {
+        $(#[$attr])*
+        impl $label for $ty {
+            fn dyn_clone(&self) -> Box<dyn $label> {
+                Box::new(self.clone())
+            }
+
+            fn as_dyn_eq(&self) -> &dyn labels::DynEq {
+                self
+            }
+
+            fn as_dyn_hash(&self) -> &dyn labels::DynHash {
+                self
+            }
+
+            $($($method)*)?
+        }
+    };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Root;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Mid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Leaf;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Other(u32);
+
+impl_label!(TestLabel for Root);
+impl_label!(#[cfg_attr(feature = "derive", labels::parent(Root))] TestLabel for Mid);
+impl_label!(#[cfg_attr(feature = "derive", labels::parent(Mid))] TestLabel for Leaf);
+impl_label!(TestLabel for Other);
+
+#[test]
+#[cfg(feature = "derive")]
+fn label_hierarchy() {
+    // Interning a label registers its parent, which is interned first.
+    let leaf = Leaf.intern();
+    assert_eq!(leaf.parent(), Some(Mid.intern()));
+    assert_eq!(Leaf.parent(), Some(Mid.intern()));
+    assert!(Leaf.is_descendant_of(&Mid));
+    assert!(Leaf.is_descendant_of(&Root));
+    assert!(Mid.is_descendant_of(&Root));
+    assert!(Leaf.intern().is_descendant_of(&Root));
+
+    assert!(!Root.is_descendant_of(&Leaf));
+    assert!(!Mid.is_descendant_of(&Leaf));
+    assert!(!Leaf.is_descendant_of(&Leaf));
+    assert!(!Leaf.is_descendant_of(&Other(0)));
+    assert_eq!(Root.parent(), None);
+}
+
+#[test]
+fn boxed_from_label() {
+    let boxed: Box<dyn TestLabel> = Other(3).into();
+    assert_eq!(format!("{boxed:?}"), "Other(3)");
+    assert!(*boxed == *Other(3).dyn_clone());
+    assert_eq!(TESTLABEL_INTERNER.intern(&*boxed), Other(3).intern());
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Spanned {
+    name: &'static str,
+    span: Ignored<(u32, u32)>,
+}
+
+impl_label!(TestLabel for Spanned);
+
+#[test]
+fn ignored_fields_dedup() {
+    let first = Spanned {
+        name: "spanned",
+        span: Ignored((1, 5)),
+    };
+    let second = Spanned {
+        name: "spanned",
+        span: Ignored((10, 14)),
+    };
+    let third = Spanned {
+        name: "other",
+        span: Ignored((1, 5)),
+    };
+
+    assert_eq!(first.intern(), second.intern());
+    assert_ne!(first.intern(), third.intern());
+    let interned = second.intern();
+    let kept = labels::DynEq::as_any(interned.as_dyn_eq())
+        .downcast_ref::<Spanned>()
+        .unwrap();
+    assert_eq!(*kept.span, (1, 5));
+}
+
+#[test]
+fn interned_equals_boxed() {
+    let interned = Other(7).intern();
+    let boxed: Box<dyn TestLabel> = Box::new(Other(7));
+    assert!(interned == boxed);
+    assert!(boxed == interned);
+
+    let different: Box<dyn TestLabel> = Box::new(Other(8));
+    assert!(interned != different);
+    assert!(different != interned);
+    let root: Box<dyn TestLabel> = Box::new(Root);
+    assert!(interned != root);
+}
+
+#[test]
+fn matches_label() {
+    let interned = Other(1).intern();
+    let boxed: Box<dyn TestLabel> = Box::new(Leaf);
+
+    assert!(matches_test_label!(interned, Other));
+    assert!(!matches_test_label!(interned, Leaf));
+    assert!(matches_test_label!(boxed, Leaf,));
+    assert!(matches_test_label!(&Root as &dyn TestLabel, Root));
+
+    let kind = match interned {
+        label if matches_test_label!(label, Leaf) => "leaf",
+        label if matches_test_label!(label, Other) => "other",
+        _ => "unknown",
+    };
+    assert_eq!(kind, "other");
+}
+
+fn collect_labels(labels: impl IntoTestLabels) -> Vec<Interned<dyn TestLabel>> {
+    labels.into_test_labels().collect()
+}
+
+#[test]
+fn tuples_into_labels() {
+    assert_eq!(
+        collect_labels((Leaf, Other(2), Root)),
+        [Leaf.intern(), Other(2).intern(), Root.intern()]
+    );
+    assert_eq!(collect_labels(Mid), [Mid.intern()]);
+    assert_eq!(collect_labels((Root, Root)), [Root.intern(), Root.intern()]);
+}
+
+#[test]
+fn per_type_cap_only_limits_one_type() {
+    let interner: Interner<dyn TestLabel> = Interner::new();
+    interner.set_per_type_cap(TypeId::of::<Other>(), 3);
+    let capped: Vec<_> = (0..3)
+        .map(|i| interner.try_intern(&Other(i)).unwrap())
+        .collect();
+
+    assert_eq!(
+        interner.try_intern(&Other(3)),
+        Err(InternError::TypeCapReached)
+    );
+    assert_eq!(interner.try_intern(&Other(1)), Ok(capped[1]));
+    for label in [&Root as &dyn TestLabel, &Mid, &Leaf] {
+        assert!(interner.try_intern(label).is_ok());
+    }
+    assert_eq!(interner.len(), 6);
+}
+
+mod first_intern_order {
+    use labels::define_label;
+
+    define_label!(OrderLabel);
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct A;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct B(u32);
+
+    impl_label!(OrderLabel for A);
+    impl_label!(OrderLabel for B);
+
+    #[test]
+    fn in_first_intern_order() {
+        let order = [B(2).intern(), A.intern(), B(1).intern()];
+        let _ = A.intern();
+        let _ = B(2).intern();
+        assert_eq!(<dyn OrderLabel>::in_first_intern_order(), order);
+    }
+}
+
+mod bridge {
+    use labels::{define_label, impl_label_bridge};
+
+    define_label!(OldLabel);
+    define_label!(NewLabel);
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Shared(u32);
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct OnlyOld;
+
+    impl_label!(OldLabel for Shared);
+    impl_label!(NewLabel for Shared);
+    impl_label!(OldLabel for OnlyOld);
+
+    impl_label_bridge!(OldLabel <-> NewLabel: Shared);
+
+    #[test]
+    fn bridge_there_and_back() {
+        let old = OldLabel::intern(&Shared(3));
+        let new = old_label_to_new_label(old).unwrap();
+        assert!(new == NewLabel::intern(&Shared(3)));
+        assert_eq!(new.as_dyn_eq().as_any().downcast_ref(), Some(&Shared(3)));
+
+        let back = new_label_to_old_label(new).unwrap();
+        assert!(back.ptr_eq(&old));
+        assert_eq!(old_label_to_new_label(OnlyOld.intern()), None);
+    }
+}
+
+mod sort_by_first_intern {
+    use labels::define_label;
+
+    define_label!(SortLabel; ord_by_first_intern);
+
+    /// The hash uninterned labels are ordered by.
+    fn hash(label: &dyn SortLabel) -> u64 {
+        let mut state = labels::StableHasher::new();
+        label.as_dyn_hash().dyn_hash(&mut state);
+        std::hash::Hasher::finish(&state)
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct C(u32);
+
+    impl_label!(SortLabel for C);
+
+    fn boxed(value: u32) -> Box<dyn SortLabel> {
+        Box::new(C(value))
+    }
+
+    #[test]
+    fn sort_without_interning() {
+        let _ = C(2).intern();
+        let _ = C(0).intern();
+        let mut labels = [boxed(5), boxed(0), boxed(9), boxed(2), boxed(5)];
+        let lookups = |stats: labels::intern::InternStats| (stats.hits, stats.misses);
+        let before = lookups(SORTLABEL_INTERNER.stats());
+        labels.sort();
+        assert_eq!(
+            lookups(SORTLABEL_INTERNER.stats()),
+            before,
+            "comparing labels is not counted as lookups"
+        );
+
+        let values: Vec<u32> = labels
+            .iter()
+            .map(|label| label.as_dyn_eq().as_any().downcast_ref::<C>().unwrap().0)
+            .collect();
+        assert_eq!(values[..2], [2, 0]);
+        let mut uninterned = values[2..].to_vec();
+        uninterned.sort_unstable();
+        assert_eq!(uninterned, [5, 5, 9]);
+        assert_eq!(
+            hash(&C(5)) < hash(&C(9)),
+            values[2] == 5,
+            "uninterned labels are ordered by their hash"
+        );
+        assert_eq!(SORTLABEL_INTERNER.len(), 2);
+
+        SORTLABEL_INTERNER.seal();
+        assert_eq!(*boxed(5) < *boxed(9), hash(&C(5)) < hash(&C(9)));
+        assert_eq!((*boxed(7)).cmp(&*boxed(7)), std::cmp::Ordering::Equal);
+        assert!(*boxed(2) < *boxed(7));
+    }
+}
+
+mod stable_tags {
+    use std::hash::{Hash, Hasher};
+
+    use labels::{define_label, StableHasher};
+
+    define_label!(TaggedLabel; stable_hash);
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Before(u32);
+
+    /// The same label as `Before`, as if it was renamed or moved to another crate.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct After(u32);
+
+    impl_label!(TaggedLabel for Before, fn type_tag(&self) -> &'static str {
+        "label.v1"
+    });
+    impl_label!(TaggedLabel for After, fn type_tag(&self) -> &'static str {
+        "label.v1"
+    });
+
+    #[test]
+    fn stable_hash_follows_the_declared_tag() {
+        assert_eq!(Before(4).stable_hash(), After(4).stable_hash());
+        assert_ne!(Before(4).stable_hash(), Before(5).stable_hash());
+        assert!(Before(4).intern() != After(4).intern());
+
+        let mut state = StableHasher::new();
+        state.write(&8u64.to_le_bytes());
+        state.write(b"label.v1");
+        4u32.hash(&mut state);
+        assert_eq!(After(4).stable_hash(), state.finish());
+    }
+}
+
+mod flags {
+    use labels::define_label;
+
+    define_label!(DefaultLabel; interned);
+
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+    struct Unit;
+
+    impl_label!(DefaultLabel for Unit);
+
+    #[test]
+    fn interned_constructor() {
+        assert_eq!(Unit::interned(), Unit.intern());
+    }
+
+    // Without their flags, the generated methods leave their names to the trait.
+    define_label!(
+        PlainLabel;
+        extra_methods:{
+            fn parent(&self) -> u8 { 1 }
+            fn is_descendant_of(&self) -> u8 { 2 }
+            fn type_tag(&self) -> u8 { 3 }
+            fn stable_hash(&self) -> u8 { 4 }
+            fn interned(&self) -> u8 { 5 }
+        }
+        extra_methods_impl:{}
+    );
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Plain;
+
+    impl_label!(PlainLabel for Plain);
+
+    #[test]
+    fn extra_methods_named_like_flagged_methods() {
+        let methods = [
+            Plain.parent(),
+            Plain.is_descendant_of(),
+            Plain.type_tag(),
+            Plain.stable_hash(),
+            Plain.interned(),
+        ];
+        assert_eq!(methods, [1, 2, 3, 4, 5]);
+        assert_eq!(Plain.intern().parent(), 1);
+    }
+}
+
+mod known_names {
+    use labels::define_label;
+
+    define_label!(NamedLabel);
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Build;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Stage(&'static str);
+
+    impl_label!(NamedLabel for Build);
+    impl_label!(NamedLabel for Stage);
+
+    #[test]
+    fn known_names_lists_interned_labels() {
+        let _ = Stage("lint").intern();
+        let _ = Build.intern();
+        let _ = Stage("test").intern();
+        let _ = Build.intern();
+
+        let names = <dyn NamedLabel>::known_names();
+        assert_eq!(names, [r#"Stage("lint")"#, "Build", r#"Stage("test")"#]);
+        let again = <dyn NamedLabel>::known_names();
+        assert!(names.iter().zip(&again).all(|(a, b)| std::ptr::eq(*a, *b)));
+    }
+}
@@ -0,0 +1,58 @@
+use std::hash::Hasher;
+
+use labels::intern::{Internable, Interner, LocalInterner, ToBoxed};
+
+#[test]
+fn handles_stay_valid_while_the_interner_grows() {
+    let interner: LocalInterner<str> = LocalInterner::new();
+    let first = interner.intern("first");
+    let first_ptr = first.0 as *const str;
+    let values: Vec<String> = (0..10_000).map(|i| format!("value_{i}")).collect();
+    let handles: Vec<_> = values.iter().map(|value| interner.intern(value)).collect();
+
+    assert_eq!(&*first, "first");
+    assert!(std::ptr::eq(first.0, first_ptr));
+    assert_eq!(interner.intern("first"), first);
+    for (value, handle) in values.iter().zip(&handles) {
+        assert_eq!(&**handle, value);
+        assert_eq!(interner.intern(value), *handle);
+    }
+    assert_eq!(interner.len(), values.len() + 1);
+}
+
+#[test]
+fn slices_are_copied_into_the_interner() {
+    let interner: LocalInterner<[u32]> = LocalInterner::new();
+    let mut values = vec![1, 2, 3];
+    let interned = interner.intern(&values);
+    values[0] = 7;
+    assert_eq!(*interned, [1, 2, 3]);
+    assert_eq!(interner.intern(&[1, 2, 3]), interned);
+    assert_eq!(*[4u32, 5].to_boxed(), [4, 5]);
+}
+
+/// A value implementing [`Internable`] by hand, without [`ToBoxed`].
+#[derive(PartialEq, Eq, Hash)]
+struct Manual(u32);
+
+impl Internable for Manual {
+    fn leak(&self) -> &'static Self {
+        Box::leak(Box::new(Manual(self.0)))
+    }
+
+    fn ref_eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+
+    fn ref_hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize((self as *const Self).addr());
+    }
+}
+
+#[test]
+fn leaking_interners_do_not_need_to_boxed() {
+    let interner: Interner<Manual> = Interner::new();
+    let value = interner.intern(&Manual(1));
+    assert!(interner.intern(&Manual(1)).ptr_eq(&value));
+    assert_eq!((*value).0, 1);
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "mmap")]
+
+use labels::intern::MmapStringInterner;
+
+#[test]
+fn resolve_from_mapped_table() {
+    let path = std::env::temp_dir().join(format!("labels-mmap-{}.table", std::process::id()));
+    let mut file = std::fs::File::create(&path).unwrap();
+    MmapStringInterner::write_table(["beta", "alpha", "gamma", "alpha", ""], &mut file).unwrap();
+    drop(file);
+
+    // SAFETY: the file is not modified while it is mapped.
+    let table = unsafe { MmapStringInterner::open(&path) }.unwrap();
+    // The mapping is never unmapped, so removing the file may fail on some platforms.
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(table.len(), 4);
+    for value in ["alpha", "beta", "gamma", ""] {
+        let interned = table.get(value).unwrap();
+        assert_eq!(&*interned, value);
+        assert_eq!(table.get(value), Some(interned));
+    }
+    assert_eq!(table.get("delta"), None);
+    assert_eq!(table.get("alph"), None);
+}
+
+#[test]
+fn reject_invalid_tables() {
+    assert!(MmapStringInterner::from_bytes(&[1, 2, 3]).is_err());
+    let mut unsorted = Vec::new();
+    MmapStringInterner::write_table(["a", "b"], &mut unsorted).unwrap();
+    let len = unsorted.len();
+    unsorted.swap(len - 1, len - 2);
+    assert!(MmapStringInterner::from_bytes(unsorted.leak()).is_err());
+}
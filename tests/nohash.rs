@@ -0,0 +1,100 @@
+#![cfg(feature = "nohash-hasher")]
+
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use labels::define_label;
+use labels::intern::{Interned, Interner, NoHashInterned};
+use nohash_hasher::IntMap;
+
+define_label!(Label);
+
+macro_rules! impl_label {
+    ($($ty:ident),*) => {
+        $(
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            struct $ty;
+
+            impl Label for $ty {
+                fn dyn_clone(&self) -> Box<dyn Label> {
+                    Box::new(self.clone())
+                }
+
+                fn as_dyn_eq(&self) -> &dyn labels::DynEq {
+                    self
+                }
+
+                fn as_dyn_hash(&self) -> &dyn labels::DynHash {
+                    self
+                }
+            }
+        )*
+    };
+}
+
+impl_label!(First, Second);
+
+#[test]
+fn id_keyed_map_matches_default_hasher() {
+    let interner: Interner<str> = Interner::new();
+    let keys: Vec<Interned<str>> = (0..10_000)
+        .map(|i| interner.intern_fmt(format_args!("key_{i}")))
+        .collect();
+
+    let mut ids: IntMap<NoHashInterned<str>, usize> = IntMap::default();
+    let mut default: HashMap<Interned<str>, usize> = HashMap::new();
+    for (i, &key) in keys.iter().enumerate() {
+        ids.insert(key.into(), i);
+        default.insert(key, i);
+    }
+
+    assert_eq!(ids.len(), default.len());
+    for key in &keys {
+        assert_eq!(ids.get(&NoHashInterned(*key)), default.get(key));
+    }
+    let missing = Interned(&*Box::leak(Box::<str>::from("key_0")));
+    assert_eq!(ids.get(&NoHashInterned(missing)), None);
+}
+
+#[test]
+fn empty_values_hash_alike() {
+    let interner: Interner<str> = Interner::new();
+    let empty = interner.intern("");
+    assert!(interner.intern("").ptr_eq(&empty));
+
+    let leaked: &'static str = Box::leak(Box::<str>::from("abc"));
+    let tail = interner.intern_ref(&leaked[3..]);
+    assert_eq!(tail, empty);
+    let hasher = nohash_hasher::BuildNoHashHasher::<u64>::default();
+    assert_eq!(
+        hasher.hash_one(NoHashInterned(tail)),
+        hasher.hash_one(NoHashInterned(empty))
+    );
+
+    let mut map: IntMap<NoHashInterned<str>, usize> = IntMap::default();
+    map.insert(empty.into(), 1);
+    assert_eq!(map.get(&NoHashInterned(tail)), Some(&1));
+    assert_eq!(map.get(&NoHashInterned(Interned(&leaked[1..1]))), Some(&1));
+    map.insert(NoHashInterned(Interned(&leaked[2..2])), 2);
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn unit_labels_hash_by_type() {
+    let (first, second) = (First.intern(), Second.intern());
+    let hasher = nohash_hasher::BuildNoHashHasher::<u64>::default();
+    assert_ne!(
+        hasher.hash_one(NoHashInterned(first)),
+        hasher.hash_one(NoHashInterned(second))
+    );
+    assert_eq!(
+        hasher.hash_one(NoHashInterned(first)),
+        hasher.hash_one(NoHashInterned(First.intern()))
+    );
+
+    let mut map: IntMap<NoHashInterned<dyn Label>, usize> = IntMap::default();
+    map.insert(first.into(), 1);
+    map.insert(second.into(), 2);
+    assert_eq!(map.get(&NoHashInterned(First.intern())), Some(&1));
+    assert_eq!(map.get(&NoHashInterned(Second.intern())), Some(&2));
+}
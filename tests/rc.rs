@@ -0,0 +1,123 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use labels::intern::RcInterner;
+
+#[test]
+fn on_evict_fires_when_last_handle_drops() {
+    let interner: RcInterner<str> = RcInterner::new();
+    let evicted = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&evicted);
+    interner.on_evict(move |value| log.lock().unwrap().push(value.to_owned()));
+
+    let a = interner.intern("a");
+    let a2 = a.clone();
+    let b = interner.intern("b");
+    drop(a);
+    assert!(evicted.lock().unwrap().is_empty());
+
+    drop(a2);
+    assert_eq!(*evicted.lock().unwrap(), ["a"]);
+    assert_eq!(interner.len(), 1);
+
+    drop(b);
+    assert_eq!(*evicted.lock().unwrap(), ["a", "b"]);
+    assert!(interner.is_empty());
+}
+
+#[test]
+fn intern_with_init_runs_once_per_distinct_value() {
+    let interner: RcInterner<str> = RcInterner::new();
+    let calls = AtomicUsize::new(0);
+    let init = |value: &str| {
+        let value = value.to_owned();
+        let calls = &calls;
+        move || {
+            calls.fetch_add(1, Ordering::Relaxed);
+            value.len()
+        }
+    };
+
+    let (a, len) = interner.intern_with_init("abc", init("abc"));
+    assert_eq!(*len, 3);
+    let (a2, len2) = interner.intern_with_init("abc", init("abc"));
+    assert!(a == a2 && Arc::ptr_eq(&len, &len2));
+    let (b, _) = interner.intern_with_init("de", init("de"));
+    assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+    assert_eq!(interner.side_data::<usize>(&b).as_deref(), Some(&2));
+    assert_eq!(interner.side_data::<String>(&b), None);
+    let plain = interner.intern("plain");
+    assert_eq!(interner.side_data::<usize>(&plain), None);
+
+    // The data is dropped with the value, so interning it again runs `init` again.
+    drop((a, a2, len2));
+    assert_eq!(Arc::strong_count(&len), 1);
+    let (_a, _) = interner.intern_with_init("abc", init("abc"));
+    assert_eq!(calls.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn intern_with_init_runs_once_across_threads() {
+    let interner: RcInterner<str> = RcInterner::new();
+    let calls = AtomicUsize::new(0);
+    let keep = interner.intern("kept");
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                for _ in 0..100 {
+                    let (_, data) = interner.intern_with_init("kept", || {
+                        calls.fetch_add(1, Ordering::Relaxed);
+                        7u8
+                    });
+                    assert_eq!(*data, 7);
+                }
+            });
+        }
+    });
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+    drop(keep);
+}
+
+#[test]
+#[should_panic = "is not a `u8`"]
+fn intern_with_init_panics_on_another_type() {
+    let interner: RcInterner<str> = RcInterner::new();
+    let (_value, _) = interner.intern_with_init("value", || 1u32);
+    let _ = interner.intern_with_init("value", || 1u8);
+}
+
+#[test]
+fn concurrent_drops_free_every_value() {
+    let interner: RcInterner<str> = RcInterner::new();
+    let created = AtomicUsize::new(0);
+    let evicted = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&evicted);
+    interner.on_evict(move |_| {
+        counter.fetch_add(1, Ordering::Relaxed);
+    });
+
+    std::thread::scope(|scope| {
+        for thread in 0..8 {
+            let interner = &interner;
+            let created = &created;
+            scope.spawn(move || {
+                for i in 0..2_000 {
+                    let value = ["shared", "other"][(thread + i) % 2];
+                    let (handle, _) = interner.intern_with_init(value, || {
+                        created.fetch_add(1, Ordering::Relaxed);
+                    });
+                    let again = interner.intern(value);
+                    assert!(handle == again);
+                    assert_eq!(&*again, value);
+                }
+            });
+        }
+    });
+
+    assert!(interner.is_empty());
+    assert_eq!(
+        created.load(Ordering::Relaxed),
+        evicted.load(Ordering::Relaxed)
+    );
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "rkyv")]
+
+use labels::intern::{ArchivableInterned, Interner};
+use rkyv::rancor::Error;
+
+#[derive(rkyv::Archive, rkyv::Serialize)]
+struct Config {
+    name: ArchivableInterned,
+    size: u32,
+}
+
+#[test]
+fn round_trip_to_live_interned() {
+    let interner: Interner<str> = Interner::new();
+    let name = interner.intern("archived");
+    let config = Config {
+        name: name.into(),
+        size: 3,
+    };
+
+    let bytes = rkyv::to_bytes::<Error>(&config).unwrap();
+    let archived = rkyv::access::<ArchivedConfig, Error>(&bytes).unwrap();
+    let live = ArchivableInterned::intern_archived(&archived.name, &interner);
+
+    assert_eq!(archived.size, 3);
+    assert_eq!(&*live, "archived");
+    assert_eq!(live, name);
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "serde")]
+
+use labels::intern::{
+    serialize_as_table_id, GlobalInternable, Interned, Interner, SerializeWithTable,
+};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct Item {
+    #[serde(serialize_with = "serialize_as_table_id")]
+    kind: Interned<str>,
+    #[serde(serialize_with = "serialize_as_table_id")]
+    owner: Interned<str>,
+    count: u32,
+}
+
+#[test]
+fn shared_strings_are_written_once() {
+    let interner: Interner<str> = Interner::new();
+    let [apple, pear, alice] = ["apple", "pear", "alice"].map(|value| interner.intern(value));
+    let items = vec![
+        Item {
+            kind: apple,
+            owner: alice,
+            count: 1,
+        },
+        Item {
+            kind: pear,
+            owner: alice,
+            count: 2,
+        },
+        Item {
+            kind: apple,
+            owner: alice,
+            count: 3,
+        },
+    ];
+
+    let json = serde_json::to_value(SerializeWithTable(&items)).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([
+            [
+                { "kind": 0, "owner": 1, "count": 1 },
+                { "kind": 2, "owner": 1, "count": 2 },
+                { "kind": 0, "owner": 1, "count": 3 },
+            ],
+            ["apple", "alice", "pear"],
+        ])
+    );
+}
+
+#[test]
+fn table_id_outside_of_table_fails() {
+    let interner: Interner<str> = Interner::new();
+    let item = Item {
+        kind: interner.intern("apple"),
+        owner: interner.intern("alice"),
+        count: 1,
+    };
+    assert!(serde_json::to_string(&item).is_err());
+}
+
+#[test]
+fn deserializing_into_a_sealed_interner_fails() {
+    let existing: Interned<str> = serde_json::from_str(r#""existing""#).unwrap();
+    <str as GlobalInternable>::global_interner().seal();
+
+    let again: Interned<str> = serde_json::from_str(r#""existing""#).unwrap();
+    assert!(again.ptr_eq(&existing));
+    let err = serde_json::from_str::<Interned<str>>(r#""new""#).unwrap_err();
+    assert!(err.to_string().contains("sealed"), "{err}");
+}
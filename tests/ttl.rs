@@ -0,0 +1,38 @@
+use std::time::{Duration, Instant};
+
+use labels::intern::TtlInterner;
+
+#[test]
+fn sweep_removes_expired_values() {
+    let interner: TtlInterner<str> = TtlInterner::new(Duration::from_secs(10));
+    let start = Instant::now();
+    let at = |secs| start + Duration::from_secs(secs);
+
+    let _ = interner.intern_at("stale", at(0));
+    let _ = interner.intern_at("fresh", at(0));
+    let held = interner.intern_at("held", at(0));
+    let _ = interner.intern_at("fresh", at(8));
+    assert_eq!(interner.sweep(at(9)), 0);
+
+    assert_eq!(interner.sweep(at(12)), 1);
+    assert_eq!(interner.len(), 2);
+    let fresh = interner.intern_at("fresh", at(12));
+    assert!(interner.intern_at("held", at(12)) == held);
+
+    drop((held, fresh));
+    assert_eq!(interner.sweep(at(21)), 0);
+    assert_eq!(interner.sweep(at(22)), 2);
+    assert!(interner.is_empty());
+}
+
+#[test]
+fn expired_values_are_interned_anew() {
+    let interner: TtlInterner<[u8]> = TtlInterner::new(Duration::from_secs(1));
+    let start = Instant::now();
+    let _ = interner.intern_at(b"value", start);
+
+    assert_eq!(interner.sweep(start + Duration::from_secs(1)), 1);
+    let again = interner.intern_at(b"value", start + Duration::from_secs(2));
+    assert_eq!(&*again, b"value");
+    assert_eq!(interner.len(), 1);
+}